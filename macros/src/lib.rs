@@ -0,0 +1,50 @@
+use abbreviations::ABBREVIATIONS;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Attribute, Fields, ItemStruct, parse_macro_input};
+
+mod abbreviations;
+
+/// Applies the Home Assistant abbreviation of each field name as its `serde` rename,
+/// e.g. `#[serde(rename = "cmd_t")]` for `command_topic`.
+///
+/// Fields that already have a `serde` rename, flattened fields and fields without a known abbreviation are left as is.
+/// Must be placed before `#[derive(Serialize)]`.
+#[proc_macro_attribute]
+pub fn ha_entity(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut item = parse_macro_input!(item as ItemStruct);
+    if let Fields::Named(fields) = &mut item.fields {
+        for field in fields.named.iter_mut() {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+            if field.attrs.iter().any(is_renamed_or_flattened) {
+                continue;
+            }
+            let name = ident.to_string();
+            if let Some((_, abbreviation)) = ABBREVIATIONS.iter().find(|(long, _)| *long == name) {
+                field
+                    .attrs
+                    .push(syn::parse_quote!(#[serde(rename = #abbreviation)]));
+            }
+        }
+    }
+    quote!(#item).into()
+}
+
+fn is_renamed_or_flattened(attr: &Attribute) -> bool {
+    if !attr.path().is_ident("serde") {
+        return false;
+    }
+    let mut found = false;
+    let _ = attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("rename") || meta.path.is_ident("flatten") {
+            found = true;
+        }
+        if meta.input.peek(syn::Token![=]) {
+            meta.value()?.parse::<syn::Expr>()?;
+        }
+        Ok(())
+    });
+    found
+}
@@ -0,0 +1,135 @@
+//! Conversion between the abbreviated attribute names of discovery payloads and the full names
+//! used in the YAML configuration of Home Assistant, e.g. `cmd_t` and `command_topic`.
+
+#[cfg(feature = "yaml")]
+use serde_json::{Map, Value};
+
+// The entity attributes are shared with the `ha_entity` attribute macro.
+#[cfg(feature = "yaml")]
+include!("../macros/src/abbreviations.rs");
+
+/// Abbreviations of the attributes of the `device` object, keyed by the full attribute name.
+#[cfg(feature = "yaml")]
+const DEVICE_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("configuration_url", "cu"),
+    ("connections", "cns"),
+    ("hw_version", "hw"),
+    ("identifiers", "ids"),
+    ("manufacturer", "mf"),
+    ("model", "mdl"),
+    ("model_id", "mdl_id"),
+    ("serial_number", "sn"),
+    ("suggested_area", "sa"),
+    ("sw_version", "sw"),
+];
+
+/// Abbreviations of the attributes of the `origin` object, keyed by the full attribute name.
+#[cfg(feature = "yaml")]
+const ORIGIN_ABBREVIATIONS: &[(&str, &str)] = &[("sw_version", "sw")];
+
+/// The topic attributes Home Assistant publishes to, by their abbreviation or, lacking one,
+/// their full name. All other topic attributes are subscribed to.
+pub(crate) const PUBLISH_TOPICS: &[&str] = &[
+    "aux_cmd_t",
+    "away_mode_cmd_t",
+    "bri_cmd_t",
+    "clr_temp_cmd_t",
+    "cmd_t",
+    "dir_cmd_t",
+    "dock_command_topic",
+    "fan_mode_cmd_t",
+    "fx_cmd_t",
+    "hs_cmd_t",
+    "hum_cmd_t",
+    "mode_cmd_t",
+    "osc_cmd_t",
+    "pause_command_topic",
+    "pct_cmd_t",
+    "power_command_topic",
+    "pr_mode_cmd_t",
+    "rgb_cmd_t",
+    "rgbw_cmd_t",
+    "rgbww_cmd_t",
+    "send_cmd_t",
+    "set_fan_spd_t",
+    "set_pos_t",
+    "start_mowing_command_topic",
+    "swing_horizontal_mode_command_topic",
+    "swing_mode_cmd_t",
+    "temp_cmd_t",
+    "temp_hi_cmd_t",
+    "temp_lo_cmd_t",
+    "tilt_cmd_t",
+    "whit_cmd_t",
+    "xy_cmd_t",
+];
+
+/// Replaces the full attribute names of an entity configuration with their abbreviations,
+/// including the ones of the `device`, `origin` and `availability` objects. Unknown names are kept.
+#[cfg(feature = "yaml")]
+pub(crate) fn abbreviate(value: Value) -> Value {
+    rename_keys(value, |table, key| {
+        table
+            .iter()
+            .find(|(full, _)| *full == key)
+            .map(|(_, abbreviation)| *abbreviation)
+    })
+}
+
+/// Replaces the abbreviated attribute names of an entity configuration with their full names,
+/// the reverse of [abbreviate].
+#[cfg(feature = "yaml")]
+pub(crate) fn expand(value: Value) -> Value {
+    rename_keys(value, |table, key| {
+        table
+            .iter()
+            .find(|(_, abbreviation)| *abbreviation == key)
+            .map(|(full, _)| *full)
+    })
+}
+
+#[cfg(feature = "yaml")]
+fn rename_keys(
+    value: Value,
+    rename: impl Fn(&[(&'static str, &'static str)], &str) -> Option<&'static str> + Copy,
+) -> Value {
+    let Value::Object(attributes) = value else {
+        return value;
+    };
+    let rename_object = |value: Value, table| match value {
+        Value::Object(object) => Value::Object(rename_object_keys(object, table, rename)),
+        value => value,
+    };
+    let mut renamed = Map::new();
+    for (key, value) in attributes {
+        let key = rename(ABBREVIATIONS, &key).map_or(key, str::to_string);
+        let value = match key.as_str() {
+            "device" | "dev" => rename_object(value, DEVICE_ABBREVIATIONS),
+            "origin" | "o" => rename_object(value, ORIGIN_ABBREVIATIONS),
+            "availability" | "avty" => match value {
+                Value::Array(checks) => Value::Array(
+                    checks
+                        .into_iter()
+                        .map(|check| rename_object(check, ABBREVIATIONS))
+                        .collect(),
+                ),
+                value => value,
+            },
+            _ => value,
+        };
+        renamed.insert(key, value);
+    }
+    Value::Object(renamed)
+}
+
+#[cfg(feature = "yaml")]
+fn rename_object_keys(
+    object: Map<String, Value>,
+    table: &[(&'static str, &'static str)],
+    rename: impl Fn(&[(&'static str, &'static str)], &str) -> Option<&'static str>,
+) -> Map<String, Value> {
+    object
+        .into_iter()
+        .map(|(key, value)| (rename(table, &key).map_or(key, str::to_string), value))
+        .collect()
+}
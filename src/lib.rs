@@ -15,11 +15,19 @@ use rumqttc::v5::{
 };
 use serde::Serialize;
 
+use crate::mqtt::common::{
+    Availability, Device, MqttDefaults, Origin, Topic, validate_json_attributes, validate_object_id,
+};
 use crate::mqtt::light::Light;
 use crate::mqtt::notify::Notify;
 pub use rumqttc::v5;
 use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
 
+mod abbreviations;
+#[macro_use]
+mod macros;
 pub mod mqtt;
 
 const ONE_WEEK_SECONDS: u32 = 60 * 60 * 24 * 7;
@@ -61,11 +69,7 @@ impl HomeAssistantMqtt {
             ))?
             .as_str()
             .ok_or(anyhow!("'uniq_id' attribute should be a string"))?;
-        let prefix = self
-            .discovery_prefix
-            .strip_suffix("/")
-            .unwrap_or(&self.discovery_prefix);
-        let topic = format!("{prefix}/{component}/{object_id}/config");
+        let topic = discovery_topic(&self.discovery_prefix, component, None, object_id);
         let payload = serde_json::ser::to_string(&attributes).unwrap();
         let props = PublishProperties {
             //payload_format_indicator: Some(1),
@@ -98,6 +102,342 @@ impl HomeAssistantMqtt {
     }
 }
 
+/// Builds the discovery topic `<discovery_prefix>/<component>/[<node_id>/]<object_id>/config`.
+fn discovery_topic(
+    discovery_prefix: &str,
+    component: &str,
+    node_id: Option<&str>,
+    object_id: &str,
+) -> String {
+    let prefix = discovery_prefix
+        .strip_suffix("/")
+        .unwrap_or(discovery_prefix);
+    match node_id {
+        Some(node_id) => format!("{prefix}/{component}/{node_id}/{object_id}/config"),
+        None => format!("{prefix}/{component}/{object_id}/config"),
+    }
+}
+
+/// Lists the attributes of `input` missing from `output`, recursing into nested objects.
+fn unknown_attributes(input: &Value, output: &Value, path: &str) -> Vec<String> {
+    const AVAILABILITY_ATTRIBUTES: [&str; 3] = ["avty_mode", "avty", "exp_aft"];
+    let (Some(input), Some(output)) = (input.as_object(), output.as_object()) else {
+        return Vec::new();
+    };
+    let mut unknown = Vec::new();
+    for (key, value) in input {
+        let key_path = format!("{path}{key}");
+        match output.get(key) {
+            Some(output_value) => unknown.extend(unknown_attributes(
+                value,
+                output_value,
+                &format!("{key_path}."),
+            )),
+            None if value.is_null() => {}
+            None if path.is_empty() && AVAILABILITY_ATTRIBUTES.contains(&key.as_str()) => {}
+            None => unknown.push(key_path),
+        }
+    }
+    unknown
+}
+
+/// Checks the topics and the object ID of an entity, as [validate_attribute_topics] does for its
+/// serialized configuration. Shared by the `validate` methods of the entities.
+pub(crate) fn validate_topics<T: Serialize>(entity: &T) -> Result<()> {
+    validate_attribute_topics(&serde_json::to_value(entity)?)
+}
+
+/// Checks every topic attribute (`~`, the abbreviated `*_t` and full `*_topic` keys) and the object ID of
+/// the serialized entity. Topics Home Assistant publishes to, listed in [abbreviations::PUBLISH_TOPICS],
+/// must not contain wildcards. Empty values are skipped, as they stand for topics that aren't set.
+fn validate_attribute_topics(attributes: &Value) -> Result<()> {
+    match attributes {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    (_, Value::String(value)) if value.is_empty() => {}
+                    ("obj_id", Value::String(object_id)) => validate_object_id(object_id)
+                        .map_err(|e| anyhow!("obj_id '{object_id}': {e}"))?,
+                    (key, Value::String(topic)) if abbreviations::PUBLISH_TOPICS.contains(&key) => {
+                        Topic::publish(topic.as_str())
+                            .map_err(|e| anyhow!("{key} '{topic}': {e}"))?;
+                    }
+                    ("~" | "t", Value::String(topic)) => {
+                        Topic::subscribe(topic.as_str())
+                            .map_err(|e| anyhow!("{key} '{topic}': {e}"))?;
+                    }
+                    (key, Value::String(topic))
+                        if key.ends_with("_t") || key.ends_with("_topic") =>
+                    {
+                        Topic::subscribe(topic.as_str())
+                            .map_err(|e| anyhow!("{key} '{topic}': {e}"))?;
+                    }
+                    _ => validate_attribute_topics(value)?,
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                validate_attribute_topics(value)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Rebuilds every object of `value` with its keys sorted. Objects keep their insertion order
+/// when any crate enables the `preserve_order` feature of `serde_json`, so it isn't relied upon.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(attributes) => {
+            let mut attributes: Vec<_> = attributes.into_iter().collect();
+            attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                attributes
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+/// The 64-bit FNV-1a hash of `bytes`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// An error found while validating an entity.
+pub type EntityError = anyhow::Error;
+
+/// Returned by [`Entity::to_bounded_json`] when the discovery configuration exceeds the size limit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayloadTooLarge {
+    /// Size of the compact JSON configuration, in bytes.
+    pub size: usize,
+    /// The limit that was exceeded, in bytes.
+    pub max_bytes: usize,
+}
+
+impl fmt::Display for PayloadTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "discovery payload is {} bytes, exceeding the limit of {} bytes",
+            self.size, self.max_bytes
+        )
+    }
+}
+
+impl std::error::Error for PayloadTooLarge {}
+
+/// Validates every entity, collecting the errors of all invalid entities along with their index.
+pub fn validate_all(entities: &[Entity]) -> Vec<(usize, EntityError)> {
+    entities
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entity)| entity.validate().err().map(|error| (index, error)))
+        .collect()
+}
+
+/// Turns `name` into an object ID the way Home Assistant slugifies names: lowercase ASCII letters and digits
+/// separated by single underscores. Accented Latin letters lose their accent, other characters act as separators.
+/// Yields `unknown` when nothing is left, as Home Assistant does.
+pub fn slugify_object_id(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        let c = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+            'ç' => 'c',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ñ' => 'n',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'ý' | 'ÿ' => 'y',
+            c => c,
+        };
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if c == 'ß' {
+            slug.push_str("ss");
+        } else if !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    let slug = slug.trim_end_matches('_');
+    if slug.is_empty() {
+        "unknown".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Checks that no two entities share a unique ID, as Home Assistant raises an exception on duplicates.
+/// Returns every duplicated ID, once, in order of first appearance.
+pub fn check_unique_ids(entities: &[Entity]) -> std::result::Result<(), Vec<String>> {
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+    for unique_id in entities.iter().filter_map(Entity::unique_id) {
+        if !seen.insert(unique_id) && !duplicates.iter().any(|d| d == unique_id) {
+            duplicates.push(unique_id.to_string());
+        }
+    }
+    if duplicates.is_empty() {
+        Ok(())
+    } else {
+        Err(duplicates)
+    }
+}
+
+/// Splits a flat map of JSON attributes into objects whose compact JSON takes at most `max_bytes` bytes each,
+/// to publish them one after another on a `json_attributes_topic`. Attributes keep their order and aren't split,
+/// so an attribute that doesn't fit on its own yields [PayloadTooLarge] with the size of its object.
+pub fn split_attributes(
+    attributes: &serde_json::Map<String, Value>,
+    max_bytes: usize,
+) -> std::result::Result<Vec<Value>, PayloadTooLarge> {
+    const BRACES: usize = "{}".len();
+    let mut chunks = Vec::new();
+    let mut chunk = serde_json::Map::new();
+    let mut chunk_size = BRACES;
+    for (key, value) in attributes {
+        // `"key":value`, serializing a string and a JSON value can't fail.
+        let entry_size = serde_json::to_string(key).unwrap().len()
+            + 1
+            + serde_json::to_string(value).unwrap().len();
+        if BRACES + entry_size > max_bytes {
+            return Err(PayloadTooLarge {
+                size: BRACES + entry_size,
+                max_bytes,
+            });
+        }
+        // Entries after the first one are preceded by a comma.
+        if !chunk.is_empty() && chunk_size + 1 + entry_size > max_bytes {
+            chunks.push(Value::Object(std::mem::take(&mut chunk)));
+            chunk_size = BRACES;
+        }
+        chunk_size += usize::from(!chunk.is_empty()) + entry_size;
+        chunk.insert(key.clone(), value.clone());
+    }
+    if !chunk.is_empty() {
+        chunks.push(Value::Object(chunk));
+    }
+    Ok(chunks)
+}
+
+/// The kind of an [Entity], without its configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EntityKind {
+    AlarmControlPanel,
+    BinarySensor,
+    Button,
+    Camera,
+    Climate,
+    Cover,
+    DeviceTracker,
+    DeviceTrigger,
+    Event,
+    Fan,
+    Humidifier,
+    Image,
+    LawnMower,
+    Light,
+    Lock,
+    Notify,
+    Number,
+    Scene,
+    Select,
+    Sensor,
+    Siren,
+    Switch,
+    Tag,
+    Text,
+    Update,
+    Vacuum,
+    Valve,
+    WaterHeater,
+}
+
+impl EntityKind {
+    /// The name of the MQTT component used in the discovery topic, e.g. `binary_sensor`.
+    pub fn component_name(self) -> &'static str {
+        match self {
+            EntityKind::AlarmControlPanel => "alarm_control_panel",
+            EntityKind::BinarySensor => "binary_sensor",
+            EntityKind::Button => "button",
+            EntityKind::Camera => "camera",
+            EntityKind::Climate => "climate",
+            EntityKind::Cover => "cover",
+            EntityKind::DeviceTracker => "device_tracker",
+            EntityKind::DeviceTrigger => "device_trigger",
+            EntityKind::Event => "event",
+            EntityKind::Fan => "fan",
+            EntityKind::Humidifier => "humidifier",
+            EntityKind::Image => "image",
+            EntityKind::LawnMower => "lawn_mower",
+            EntityKind::Light => "light",
+            EntityKind::Lock => "lock",
+            EntityKind::Notify => "notify",
+            EntityKind::Number => "number",
+            EntityKind::Scene => "scene",
+            EntityKind::Select => "select",
+            EntityKind::Sensor => "sensor",
+            EntityKind::Siren => "siren",
+            EntityKind::Switch => "switch",
+            EntityKind::Tag => "tag",
+            EntityKind::Text => "text",
+            EntityKind::Update => "update",
+            EntityKind::Vacuum => "vacuum",
+            EntityKind::Valve => "valve",
+            EntityKind::WaterHeater => "water_heater",
+        }
+    }
+
+    /// The fields Home Assistant requires for this kind of entity, e.g. `command_topic` for a number.
+    /// `platform` is always set by this crate and isn't listed, nor are alternatives of which only one
+    /// is needed, such as the `image_topic` and `url_topic` of an image.
+    pub fn required_fields(self) -> &'static [&'static str] {
+        match self {
+            EntityKind::AlarmControlPanel => &["command_topic", "state_topic"],
+            EntityKind::BinarySensor => &["state_topic"],
+            EntityKind::Button => &["command_topic"],
+            EntityKind::Camera => &["topic"],
+            EntityKind::DeviceTrigger => &["automation_type", "device", "subtype", "topic", "type"],
+            EntityKind::Event => &["event_types", "state_topic"],
+            EntityKind::Fan => &["command_topic"],
+            EntityKind::Humidifier => &["command_topic", "target_humidity_command_topic"],
+            EntityKind::Light => &["command_topic"],
+            EntityKind::Lock => &["command_topic"],
+            EntityKind::Number => &["command_topic"],
+            EntityKind::Select => &["command_topic", "options"],
+            EntityKind::Sensor => &["state_topic"],
+            EntityKind::Switch => &["command_topic"],
+            EntityKind::Tag => &["device", "topic"],
+            EntityKind::Text => &["command_topic"],
+            EntityKind::Climate
+            | EntityKind::Cover
+            | EntityKind::DeviceTracker
+            | EntityKind::Image
+            | EntityKind::LawnMower
+            | EntityKind::Notify
+            | EntityKind::Scene
+            | EntityKind::Siren
+            | EntityKind::Update
+            | EntityKind::Vacuum
+            | EntityKind::Valve
+            | EntityKind::WaterHeater => &[],
+        }
+    }
+}
+
 #[derive(Clone)]
 pub enum Entity {
     AlarmControlPanel(AlarmControlPanel),
@@ -131,40 +471,50 @@ pub enum Entity {
 }
 
 impl Entity {
-    fn get_component_name(&self) -> &str {
+    /// Returns the kind of this entity.
+    pub fn kind(&self) -> EntityKind {
         match self {
-            Entity::AlarmControlPanel(_) => "alarm_control_panel",
-            Entity::BinarySensor(_) => "binary_sensor",
-            Entity::Button(_) => "button",
-            Entity::Camera(_) => "camera",
-            Entity::Climate(_) => "climate",
-            Entity::Cover(_) => "cover",
-            Entity::DeviceTracker(_) => "device_tracker",
-            Entity::DeviceTrigger(_) => "device_trigger",
-            Entity::Event(_) => "event",
-            Entity::Fan(_) => "fan",
-            Entity::Humidifier(_) => "humidifier",
-            Entity::Image(_) => "image",
-            Entity::LawnMower(_) => "lawn_mower",
-            Entity::Light(_) => "light",
-            Entity::Lock(_) => "lock",
-            Entity::Notify(_) => "notify",
-            Entity::Number(_) => "number",
-            Entity::Scene(_) => "scene",
-            Entity::Select(_) => "select",
-            Entity::Sensor(_) => "sensor",
-            Entity::Siren(_) => "siren",
-            Entity::Switch(_) => "switch",
-            Entity::Tag(_) => "tag",
-            Entity::Text(_) => "text",
-            Entity::Update(_) => "update",
-            Entity::Vacuum(_) => "vacuum",
-            Entity::Valve(_) => "valve",
-            Entity::WaterHeater(_) => "water_heater",
-        }
-    }
-
-    fn get_attributes(&self) -> Result<Value> {
+            Entity::AlarmControlPanel(_) => EntityKind::AlarmControlPanel,
+            Entity::BinarySensor(_) => EntityKind::BinarySensor,
+            Entity::Button(_) => EntityKind::Button,
+            Entity::Camera(_) => EntityKind::Camera,
+            Entity::Climate(_) => EntityKind::Climate,
+            Entity::Cover(_) => EntityKind::Cover,
+            Entity::DeviceTracker(_) => EntityKind::DeviceTracker,
+            Entity::DeviceTrigger(_) => EntityKind::DeviceTrigger,
+            Entity::Event(_) => EntityKind::Event,
+            Entity::Fan(_) => EntityKind::Fan,
+            Entity::Humidifier(_) => EntityKind::Humidifier,
+            Entity::Image(_) => EntityKind::Image,
+            Entity::LawnMower(_) => EntityKind::LawnMower,
+            Entity::Light(_) => EntityKind::Light,
+            Entity::Lock(_) => EntityKind::Lock,
+            Entity::Notify(_) => EntityKind::Notify,
+            Entity::Number(_) => EntityKind::Number,
+            Entity::Scene(_) => EntityKind::Scene,
+            Entity::Select(_) => EntityKind::Select,
+            Entity::Sensor(_) => EntityKind::Sensor,
+            Entity::Siren(_) => EntityKind::Siren,
+            Entity::Switch(_) => EntityKind::Switch,
+            Entity::Tag(_) => EntityKind::Tag,
+            Entity::Text(_) => EntityKind::Text,
+            Entity::Update(_) => EntityKind::Update,
+            Entity::Vacuum(_) => EntityKind::Vacuum,
+            Entity::Valve(_) => EntityKind::Valve,
+            Entity::WaterHeater(_) => EntityKind::WaterHeater,
+        }
+    }
+
+    /// The fields Home Assistant requires for this entity, see [EntityKind::required_fields].
+    pub fn required_fields(&self) -> &'static [&'static str] {
+        self.kind().required_fields()
+    }
+
+    pub(crate) fn get_component_name(&self) -> &str {
+        self.kind().component_name()
+    }
+
+    pub(crate) fn get_attributes(&self) -> Result<Value> {
         let attributes = match self {
             Entity::AlarmControlPanel(alarm_control_panel) => {
                 serde_json::to_value(alarm_control_panel)?
@@ -199,4 +549,1316 @@ impl Entity {
         };
         Ok(attributes)
     }
+
+    /// The unique ID of the entity, if it has one. Device triggers and tags don't support unique IDs.
+    pub fn unique_id(&self) -> Option<&str> {
+        match self {
+            Entity::AlarmControlPanel(alarm_control_panel) => {
+                alarm_control_panel.unique_id.as_deref()
+            }
+            Entity::BinarySensor(binary_sensor) => binary_sensor.unique_id.as_deref(),
+            Entity::Button(button) => button.unique_id.as_deref(),
+            Entity::Camera(camera) => camera.unique_id.as_deref(),
+            Entity::Climate(climate) => climate.unique_id.as_deref(),
+            Entity::Cover(cover) => cover.unique_id.as_deref(),
+            Entity::DeviceTracker(device_tracker) => device_tracker.unique_id.as_deref(),
+            Entity::DeviceTrigger(_) => None,
+            Entity::Event(event) => event.unique_id.as_deref(),
+            Entity::Fan(fan) => fan.unique_id.as_deref(),
+            Entity::Humidifier(humidifier) => humidifier.unique_id.as_deref(),
+            Entity::Image(image) => image.unique_id.as_deref(),
+            Entity::LawnMower(lawn_mower) => lawn_mower.unique_id.as_deref(),
+            Entity::Light(light) => light.unique_id.as_deref(),
+            Entity::Lock(lock) => lock.unique_id.as_deref(),
+            Entity::Notify(notify) => notify.unique_id.as_deref(),
+            Entity::Number(number) => number.unique_id.as_deref(),
+            Entity::Scene(scene) => scene.unique_id.as_deref(),
+            Entity::Select(select) => select.unique_id.as_deref(),
+            Entity::Sensor(sensor) => sensor.unique_id.as_deref(),
+            Entity::Siren(siren) => siren.unique_id.as_deref(),
+            Entity::Switch(switch) => switch.unique_id.as_deref(),
+            Entity::Tag(_) => None,
+            Entity::Text(text) => text.unique_id.as_deref(),
+            Entity::Update(update) => update.unique_id.as_deref(),
+            Entity::Vacuum(vacuum) => vacuum.unique_id.as_deref(),
+            Entity::Valve(valve) => valve.unique_id.as_deref(),
+            Entity::WaterHeater(water_heater) => water_heater.unique_id.as_deref(),
+        }
+    }
+
+    /// The name of the entity, if it has one. Device triggers and tags don't have names.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Entity::AlarmControlPanel(alarm_control_panel) => alarm_control_panel.name.as_deref(),
+            Entity::BinarySensor(binary_sensor) => binary_sensor.name.as_deref(),
+            Entity::Button(button) => button.name.as_deref(),
+            Entity::Camera(camera) => camera.name.as_deref(),
+            Entity::Climate(climate) => climate.name.as_deref(),
+            Entity::Cover(cover) => cover.name.as_deref(),
+            Entity::DeviceTracker(device_tracker) => device_tracker.name.as_deref(),
+            Entity::DeviceTrigger(_) => None,
+            Entity::Event(event) => event.name.as_deref(),
+            Entity::Fan(fan) => fan.name.as_deref(),
+            Entity::Humidifier(humidifier) => humidifier.name.as_deref(),
+            Entity::Image(image) => image.name.as_deref(),
+            Entity::LawnMower(lawn_mower) => lawn_mower.name.as_deref(),
+            Entity::Light(light) => light.name.as_deref(),
+            Entity::Lock(lock) => lock.name.as_deref(),
+            Entity::Notify(notify) => notify.name.as_deref(),
+            Entity::Number(number) => number.name.as_deref(),
+            Entity::Scene(scene) => scene.name.as_deref(),
+            Entity::Select(select) => select.name.as_deref(),
+            Entity::Sensor(sensor) => sensor.name.as_deref(),
+            Entity::Siren(siren) => siren.name.as_deref(),
+            Entity::Switch(switch) => switch.name.as_deref(),
+            Entity::Tag(_) => None,
+            Entity::Text(text) => text.name.as_deref(),
+            Entity::Update(update) => update.name.as_deref(),
+            Entity::Vacuum(vacuum) => vacuum.name.as_deref(),
+            Entity::Valve(valve) => valve.name.as_deref(),
+            Entity::WaterHeater(water_heater) => water_heater.name.as_deref(),
+        }
+    }
+
+    /// The object ID Home Assistant would derive for the entity when `object_id` isn't set:
+    /// the slugified name, or the slugified unique ID for entities without a name.
+    pub fn suggested_object_id(&self) -> Option<String> {
+        self.name().or(self.unique_id()).map(slugify_object_id)
+    }
+
+    /// Creates an entity of the named MQTT component with its default configuration, e.g. a [Cover] for `cover`,
+    /// to build entities chosen at runtime. Unknown components yield `None`.
+    pub fn default_for_component(component: &str) -> Option<Entity> {
+        let entity = match component {
+            "alarm_control_panel" => Entity::AlarmControlPanel(AlarmControlPanel::default()),
+            "binary_sensor" => Entity::BinarySensor(BinarySensor::default()),
+            "button" => Entity::Button(Button::default()),
+            "camera" => Entity::Camera(Camera::default()),
+            "climate" => Entity::Climate(Climate::default()),
+            "cover" => Entity::Cover(Cover::default()),
+            "device_tracker" => Entity::DeviceTracker(DeviceTracker::default()),
+            "device_trigger" => Entity::DeviceTrigger(DeviceTrigger::default()),
+            "event" => Entity::Event(Event::default()),
+            "fan" => Entity::Fan(Fan::default()),
+            "humidifier" => Entity::Humidifier(Humidifier::default()),
+            "image" => Entity::Image(Image::default()),
+            "lawn_mower" => Entity::LawnMower(LawnMower::default()),
+            "light" => Entity::Light(Light::default()),
+            "lock" => Entity::Lock(Lock::default()),
+            "notify" => Entity::Notify(Notify::default()),
+            "number" => Entity::Number(Number::default()),
+            "scene" => Entity::Scene(Scene::default()),
+            "select" => Entity::Select(Select::default()),
+            "sensor" => Entity::Sensor(Sensor::default()),
+            "siren" => Entity::Siren(Siren::default()),
+            "switch" => Entity::Switch(Switch::default()),
+            "tag" => Entity::Tag(Tag::default()),
+            "text" => Entity::Text(Text::default()),
+            "update" => Entity::Update(Update::default()),
+            "vacuum" => Entity::Vacuum(Vacuum::default()),
+            "valve" => Entity::Valve(Valve::default()),
+            "water_heater" => Entity::WaterHeater(WaterHeater::default()),
+            _ => return None,
+        };
+        Some(entity)
+    }
+
+    /// Lists the templates set on the entity as pairs of the full attribute name and the template,
+    /// e.g. `("position_template", "{{ value.y }}")`, ordered by attribute name.
+    /// The templates of the availability checks are listed as `availability.value_template`.
+    pub fn template_fields(&self) -> Vec<(&'static str, &str)> {
+        let mut templates = match self {
+            Entity::AlarmControlPanel(alarm_control_panel) => template_fields!(
+                alarm_control_panel,
+                command_template,
+                json_attributes_template,
+                value_template
+            ),
+            Entity::BinarySensor(binary_sensor) => {
+                template_fields!(binary_sensor, json_attributes_template, value_template)
+            }
+            Entity::Button(button) => {
+                template_fields!(button, command_template, json_attributes_template)
+            }
+            Entity::Camera(camera) => template_fields!(camera, json_attributes_template),
+            Entity::Climate(climate) => template_fields!(
+                climate,
+                action_template,
+                current_humidity_template,
+                current_temperature_template,
+                fan_mode_command_template,
+                fan_mode_state_template,
+                json_attributes_template,
+                mode_command_template,
+                mode_state_template,
+                power_command_template,
+                preset_mode_command_template,
+                preset_mode_value_template,
+                swing_horizontal_mode_command_template,
+                swing_horizontal_mode_state_template,
+                swing_mode_command_template,
+                swing_mode_state_template,
+                target_humidity_command_template,
+                target_humidity_state_template,
+                temperature_command_template,
+                temperature_high_command_template,
+                temperature_high_state_template,
+                temperature_low_command_template,
+                temperature_low_state_template,
+                temperature_state_template,
+                value_template
+            ),
+            Entity::Cover(cover) => template_fields!(
+                cover,
+                json_attributes_template,
+                position_template,
+                set_position_template,
+                tilt_command_template,
+                tilt_status_template,
+                value_template
+            ),
+            Entity::DeviceTracker(device_tracker) => {
+                template_fields!(device_tracker, json_attributes_template, value_template)
+            }
+            Entity::DeviceTrigger(device_trigger) => {
+                template_fields!(device_trigger, value_template)
+            }
+            Entity::Event(event) => {
+                template_fields!(event, json_attributes_template, value_template)
+            }
+            Entity::Fan(fan) => template_fields!(
+                fan,
+                command_template,
+                direction_command_template,
+                direction_value_template,
+                json_attributes_template,
+                oscillation_command_template,
+                oscillation_value_template,
+                percentage_command_template,
+                percentage_value_template,
+                preset_mode_command_template,
+                preset_mode_value_template,
+                state_value_template
+            ),
+            Entity::Humidifier(humidifier) => template_fields!(
+                humidifier,
+                action_template,
+                command_template,
+                current_humidity_template,
+                json_attributes_template,
+                mode_command_template,
+                mode_state_template,
+                state_value_template,
+                target_humidity_command_template,
+                target_humidity_state_template
+            ),
+            Entity::Image(image) => template_fields!(image, json_attributes_template, url_template),
+            Entity::LawnMower(lawn_mower) => template_fields!(
+                lawn_mower,
+                activity_value_template,
+                dock_command_template,
+                json_attributes_template,
+                pause_command_template,
+                start_mowing_template
+            ),
+            Entity::Light(light) => template_fields!(
+                light,
+                brightness_command_template,
+                brightness_value_template,
+                color_mode_value_template,
+                color_temp_command_template,
+                color_temp_value_template,
+                effect_command_template,
+                effect_value_template,
+                hs_command_template,
+                hs_value_template,
+                json_attributes_template,
+                rgb_command_template,
+                rgb_value_template,
+                rgbw_command_template,
+                rgbw_value_template,
+                rgbww_command_template,
+                rgbww_value_template,
+                state_value_template,
+                xy_command_template,
+                xy_value_template
+            ),
+            Entity::Lock(lock) => template_fields!(
+                lock,
+                command_template,
+                json_attributes_template,
+                value_template
+            ),
+            Entity::Notify(notify) => {
+                template_fields!(notify, command_template, json_attributes_template)
+            }
+            Entity::Number(number) => template_fields!(
+                number,
+                command_template,
+                json_attributes_template,
+                value_template
+            ),
+            Entity::Scene(scene) => template_fields!(scene, json_attributes_template),
+            Entity::Select(select) => template_fields!(
+                select,
+                command_template,
+                json_attributes_template,
+                value_template
+            ),
+            Entity::Sensor(sensor) => template_fields!(
+                sensor,
+                json_attributes_template,
+                last_reset_value_template,
+                value_template
+            ),
+            Entity::Siren(siren) => template_fields!(
+                siren,
+                command_off_template,
+                command_template,
+                json_attributes_template,
+                state_value_template
+            ),
+            Entity::Switch(switch) => template_fields!(
+                switch,
+                command_template,
+                json_attributes_template,
+                value_template
+            ),
+            Entity::Tag(tag) => template_fields!(tag, value_template),
+            Entity::Text(text) => template_fields!(
+                text,
+                command_template,
+                json_attributes_template,
+                value_template
+            ),
+            Entity::Update(update) => template_fields!(
+                update,
+                json_attributes_template,
+                latest_version_template,
+                value_template
+            ),
+            Entity::Vacuum(vacuum) => template_fields!(vacuum, json_attributes_template),
+            Entity::Valve(valve) => template_fields!(
+                valve,
+                command_template,
+                json_attributes_template,
+                value_template
+            ),
+            Entity::WaterHeater(water_heater) => template_fields!(
+                water_heater,
+                current_temperature_template,
+                json_attributes_template,
+                mode_command_template,
+                mode_state_template,
+                power_command_template,
+                temperature_command_template,
+                temperature_state_template,
+                value_template
+            ),
+        };
+        templates.extend(self.availability().availability.iter().filter_map(|check| {
+            Some((
+                "availability.value_template",
+                check.value_template.as_deref()?,
+            ))
+        }));
+        templates.sort();
+        templates
+    }
+
+    /// Parses a discovery configuration, using its `platform` attribute to choose the type of entity.
+    /// Unknown attributes are ignored.
+    pub fn from_json(value: Value) -> Result<Entity> {
+        let platform = value
+            .get("platform")
+            .and_then(Value::as_str)
+            .ok_or(anyhow!(
+                "entity configuration should have a string attribute 'platform'"
+            ))?
+            .to_string();
+        let entity = match platform.as_str() {
+            "alarm_control_panel" => Entity::AlarmControlPanel(serde_json::from_value(value)?),
+            "binary_sensor" => Entity::BinarySensor(serde_json::from_value(value)?),
+            "button" => Entity::Button(serde_json::from_value(value)?),
+            "camera" => Entity::Camera(serde_json::from_value(value)?),
+            "climate" => Entity::Climate(serde_json::from_value(value)?),
+            "cover" => Entity::Cover(serde_json::from_value(value)?),
+            "device_tracker" => Entity::DeviceTracker(serde_json::from_value(value)?),
+            "device_trigger" => Entity::DeviceTrigger(serde_json::from_value(value)?),
+            "event" => Entity::Event(serde_json::from_value(value)?),
+            "fan" => Entity::Fan(serde_json::from_value(value)?),
+            "humidifier" => Entity::Humidifier(serde_json::from_value(value)?),
+            "image" => Entity::Image(serde_json::from_value(value)?),
+            "lawn_mower" => Entity::LawnMower(serde_json::from_value(value)?),
+            "light" => Entity::Light(serde_json::from_value(value)?),
+            "lock" => Entity::Lock(serde_json::from_value(value)?),
+            "notify" => Entity::Notify(serde_json::from_value(value)?),
+            "number" => Entity::Number(serde_json::from_value(value)?),
+            "scene" => Entity::Scene(serde_json::from_value(value)?),
+            "select" => Entity::Select(serde_json::from_value(value)?),
+            "sensor" => Entity::Sensor(serde_json::from_value(value)?),
+            "siren" => Entity::Siren(serde_json::from_value(value)?),
+            "switch" => Entity::Switch(serde_json::from_value(value)?),
+            "tag" => Entity::Tag(serde_json::from_value(value)?),
+            "text" => Entity::Text(serde_json::from_value(value)?),
+            "update" => Entity::Update(serde_json::from_value(value)?),
+            "vacuum" => Entity::Vacuum(serde_json::from_value(value)?),
+            "valve" => Entity::Valve(serde_json::from_value(value)?),
+            "water_heater" => Entity::WaterHeater(serde_json::from_value(value)?),
+            _ => return Err(anyhow!("unknown platform '{platform}'")),
+        };
+        Ok(entity)
+    }
+
+    /// Parses an entry of the YAML configuration of Home Assistant, e.g. an item of the `mqtt.cover` list
+    /// for the `cover` component. The full attribute names used in YAML are abbreviated as in discovery payloads.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(component: &str, yaml: &str) -> Result<Entity> {
+        let mut value = abbreviations::abbreviate(serde_yaml::from_str(yaml)?);
+        value
+            .as_object_mut()
+            .ok_or(anyhow!("entity configuration should be a mapping"))?
+            .insert("platform".to_string(), Value::from(component));
+        Entity::from_json(value)
+    }
+
+    /// Renders the entity as an entry of the YAML configuration of Home Assistant, with full attribute names,
+    /// to be listed under the `mqtt` key of `configuration.yaml`. `platform` is left out, as it's implied there.
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> String {
+        let mut value = abbreviations::expand(
+            self.get_attributes()
+                .expect("entity configurations always serialize to JSON"),
+        );
+        if let Some(attributes) = value.as_object_mut() {
+            attributes.remove("platform");
+        }
+        serde_yaml::to_string(&value).expect("JSON values always serialize to YAML")
+    }
+
+    /// Parses a discovery configuration like [`Entity::from_json`], but rejects unknown attributes to catch typos.
+    ///
+    /// `#[serde(deny_unknown_fields)]` can't be combined with the flattened availability, so instead the parsed entity
+    /// is serialized back and every attribute of the input, including the ones of nested objects such as `dev`,
+    /// must be present in the output. The availability attributes are always accepted since they are omitted
+    /// when set to their defaults, and so are attributes set to `null`, which are parsed as unset.
+    pub fn from_strict_json(value: Value) -> Result<Entity> {
+        let entity = Entity::from_json(value.clone())?;
+        let unknown = unknown_attributes(&value, &entity.get_attributes()?, "");
+        if !unknown.is_empty() {
+            return Err(anyhow!("unknown attributes: {}", unknown.join(", ")));
+        }
+        Ok(entity)
+    }
+
+    /// Checks the configuration of the entity for mistakes Home Assistant would reject or ignore.
+    pub fn validate(&self) -> Result<()> {
+        self.availability().validate()?;
+        self.device().validate()?;
+        let attributes = self.get_attributes()?;
+        validate_json_attributes(&attributes)?;
+        // The entities with their own `validate` check their topics there.
+        match self {
+            Entity::Climate(climate) => climate.validate(),
+            Entity::Cover(cover) => cover.validate(),
+            Entity::Fan(fan) => fan.validate(),
+            Entity::LawnMower(lawn_mower) => lawn_mower.validate(),
+            Entity::Light(light) => light.validate(),
+            Entity::Number(number) => number.validate(),
+            Entity::Select(select) => select.validate(),
+            Entity::Sensor(sensor) => sensor.validate(),
+            Entity::Siren(siren) => siren.validate(),
+            Entity::Valve(valve) => valve.validate(),
+            Entity::WaterHeater(water_heater) => water_heater.validate(),
+            _ => validate_attribute_topics(&attributes),
+        }
+    }
+
+    /// Serializes the discovery configuration of the entity with its keys sorted,
+    /// so the same configuration always yields byte-identical output.
+    pub fn to_ordered_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&sort_keys(self.get_attributes()?))?)
+    }
+
+    /// Serializes the discovery configuration of the entity as compact JSON, failing when it takes more than
+    /// `max_bytes` bytes, e.g. the MQTT buffer size of a microcontroller.
+    pub fn to_bounded_json(
+        &self,
+        max_bytes: usize,
+    ) -> std::result::Result<String, PayloadTooLarge> {
+        let json = self
+            .to_ordered_json()
+            .expect("entity configurations always serialize to JSON");
+        if json.len() > max_bytes {
+            return Err(PayloadTooLarge {
+                size: json.len(),
+                max_bytes,
+            });
+        }
+        Ok(json)
+    }
+
+    /// A hash of the discovery configuration of the entity, to only republish configurations that changed.
+    ///
+    /// The 64-bit FNV-1a hash of [Entity::to_ordered_json] is used, so the hash is stable across runs and
+    /// Rust releases and can be persisted.
+    pub fn config_hash(&self) -> u64 {
+        let json = self
+            .to_ordered_json()
+            .expect("entity configurations always serialize to JSON");
+        fnv1a(json.as_bytes())
+    }
+
+    /// The message removing the entity from Home Assistant: its discovery topic and an empty payload.
+    /// The unique ID of the entity is used as `<object_id>`, as in [`HomeAssistantMqtt::publish_entity`],
+    /// so entities without a unique ID yield `None`.
+    pub fn removal_message(
+        &self,
+        discovery_prefix: &str,
+        node_id: Option<&str>,
+    ) -> Option<(String, String)> {
+        let object_id = self.unique_id()?;
+        let topic = discovery_topic(
+            discovery_prefix,
+            self.get_component_name(),
+            node_id,
+            object_id,
+        );
+        Some((topic, String::new()))
+    }
+
+    /// Fills the `qos`, `retain` and `encoding` attributes the entity supports but doesn't set
+    /// with the given defaults. Attributes that are already set are kept.
+    pub fn apply_defaults(&mut self, defaults: &MqttDefaults) {
+        fn fill<T: Clone>(field: &mut Option<T>, default: &Option<T>) {
+            if field.is_none() {
+                *field = default.clone();
+            }
+        }
+        let encoding = defaults.encoding.clone().map(String::from);
+        match self {
+            Entity::AlarmControlPanel(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::BinarySensor(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Button(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Camera(entity) => {
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Climate(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Cover(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &defaults.encoding);
+            }
+            Entity::DeviceTracker(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+            }
+            Entity::DeviceTrigger(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+            }
+            Entity::Event(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Fan(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Humidifier(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Image(entity) => {
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::LawnMower(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Light(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Lock(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Notify(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Number(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &defaults.encoding);
+            }
+            Entity::Scene(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Select(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Sensor(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Siren(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Switch(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Tag(_) => {}
+            Entity::Text(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Update(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Vacuum(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::Valve(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+            Entity::WaterHeater(entity) => {
+                fill(&mut entity.qos, &defaults.qos);
+                fill(&mut entity.retain, &defaults.retain);
+                fill(&mut entity.encoding, &encoding);
+            }
+        }
+    }
+
+    /// Replaces the origin of the entity.
+    pub(crate) fn set_origin(&mut self, origin: Origin) {
+        match self {
+            Entity::AlarmControlPanel(entity) => entity.origin = origin,
+            Entity::BinarySensor(entity) => entity.origin = origin,
+            Entity::Button(entity) => entity.origin = origin,
+            Entity::Camera(entity) => entity.origin = origin,
+            Entity::Climate(entity) => entity.origin = origin,
+            Entity::Cover(entity) => entity.origin = origin,
+            Entity::DeviceTracker(entity) => entity.origin = origin,
+            Entity::DeviceTrigger(entity) => entity.origin = origin,
+            Entity::Event(entity) => entity.origin = origin,
+            Entity::Fan(entity) => entity.origin = origin,
+            Entity::Humidifier(entity) => entity.origin = origin,
+            Entity::Image(entity) => entity.origin = origin,
+            Entity::LawnMower(entity) => entity.origin = origin,
+            Entity::Light(entity) => entity.origin = origin,
+            Entity::Lock(entity) => entity.origin = origin,
+            Entity::Notify(entity) => entity.origin = origin,
+            Entity::Number(entity) => entity.origin = origin,
+            Entity::Scene(entity) => entity.origin = origin,
+            Entity::Select(entity) => entity.origin = origin,
+            Entity::Sensor(entity) => entity.origin = origin,
+            Entity::Siren(entity) => entity.origin = origin,
+            Entity::Switch(entity) => entity.origin = origin,
+            Entity::Tag(entity) => entity.origin = origin,
+            Entity::Text(entity) => entity.origin = origin,
+            Entity::Update(entity) => entity.origin = origin,
+            Entity::Vacuum(entity) => entity.origin = origin,
+            Entity::Valve(entity) => entity.origin = origin,
+            Entity::WaterHeater(entity) => entity.origin = origin,
+        }
+    }
+
+    /// Returns the availability configuration of the entity.
+    pub(crate) fn availability(&self) -> &Availability {
+        match self {
+            Entity::AlarmControlPanel(entity) => &entity.availability,
+            Entity::BinarySensor(entity) => &entity.availability,
+            Entity::Button(entity) => &entity.availability,
+            Entity::Camera(entity) => &entity.availability,
+            Entity::Climate(entity) => &entity.availability,
+            Entity::Cover(entity) => &entity.availability,
+            Entity::DeviceTracker(entity) => &entity.availability,
+            Entity::DeviceTrigger(entity) => &entity.availability,
+            Entity::Event(entity) => &entity.availability,
+            Entity::Fan(entity) => &entity.availability,
+            Entity::Humidifier(entity) => &entity.availability,
+            Entity::Image(entity) => &entity.availability,
+            Entity::LawnMower(entity) => &entity.availability,
+            Entity::Light(entity) => &entity.availability,
+            Entity::Lock(entity) => &entity.availability,
+            Entity::Notify(entity) => &entity.availability,
+            Entity::Number(entity) => &entity.availability,
+            Entity::Scene(entity) => &entity.availability,
+            Entity::Select(entity) => &entity.availability,
+            Entity::Sensor(entity) => &entity.availability,
+            Entity::Siren(entity) => &entity.availability,
+            Entity::Switch(entity) => &entity.availability,
+            Entity::Tag(entity) => &entity.availability,
+            Entity::Text(entity) => &entity.availability,
+            Entity::Update(entity) => &entity.availability,
+            Entity::Vacuum(entity) => &entity.availability,
+            Entity::Valve(entity) => &entity.availability,
+            Entity::WaterHeater(entity) => &entity.availability,
+        }
+    }
+
+    /// The device the entity belongs to.
+    pub fn device(&self) -> &Device {
+        match self {
+            Entity::AlarmControlPanel(entity) => &entity.device,
+            Entity::BinarySensor(entity) => &entity.device,
+            Entity::Button(entity) => &entity.device,
+            Entity::Camera(entity) => &entity.device,
+            Entity::Climate(entity) => &entity.device,
+            Entity::Cover(entity) => &entity.device,
+            Entity::DeviceTracker(entity) => &entity.device,
+            Entity::DeviceTrigger(entity) => &entity.device,
+            Entity::Event(entity) => &entity.device,
+            Entity::Fan(entity) => &entity.device,
+            Entity::Humidifier(entity) => &entity.device,
+            Entity::Image(entity) => &entity.device,
+            Entity::LawnMower(entity) => &entity.device,
+            Entity::Light(entity) => &entity.device,
+            Entity::Lock(entity) => &entity.device,
+            Entity::Notify(entity) => &entity.device,
+            Entity::Number(entity) => &entity.device,
+            Entity::Scene(entity) => &entity.device,
+            Entity::Select(entity) => &entity.device,
+            Entity::Sensor(entity) => &entity.device,
+            Entity::Siren(entity) => &entity.device,
+            Entity::Switch(entity) => &entity.device,
+            Entity::Tag(entity) => &entity.device,
+            Entity::Text(entity) => &entity.device,
+            Entity::Update(entity) => &entity.device,
+            Entity::Vacuum(entity) => &entity.device,
+            Entity::Valve(entity) => &entity.device,
+            Entity::WaterHeater(entity) => &entity.device,
+        }
+    }
+
+    /// Replaces the device the entity belongs to, e.g. to attach the same device to many entities.
+    pub fn set_device(&mut self, device: Device) {
+        match self {
+            Entity::AlarmControlPanel(entity) => entity.device = device,
+            Entity::BinarySensor(entity) => entity.device = device,
+            Entity::Button(entity) => entity.device = device,
+            Entity::Camera(entity) => entity.device = device,
+            Entity::Climate(entity) => entity.device = device,
+            Entity::Cover(entity) => entity.device = device,
+            Entity::DeviceTracker(entity) => entity.device = device,
+            Entity::DeviceTrigger(entity) => entity.device = device,
+            Entity::Event(entity) => entity.device = device,
+            Entity::Fan(entity) => entity.device = device,
+            Entity::Humidifier(entity) => entity.device = device,
+            Entity::Image(entity) => entity.device = device,
+            Entity::LawnMower(entity) => entity.device = device,
+            Entity::Light(entity) => entity.device = device,
+            Entity::Lock(entity) => entity.device = device,
+            Entity::Notify(entity) => entity.device = device,
+            Entity::Number(entity) => entity.device = device,
+            Entity::Scene(entity) => entity.device = device,
+            Entity::Select(entity) => entity.device = device,
+            Entity::Sensor(entity) => entity.device = device,
+            Entity::Siren(entity) => entity.device = device,
+            Entity::Switch(entity) => entity.device = device,
+            Entity::Tag(entity) => entity.device = device,
+            Entity::Text(entity) => entity.device = device,
+            Entity::Update(entity) => entity.device = device,
+            Entity::Vacuum(entity) => entity.device = device,
+            Entity::Valve(entity) => entity.device = device,
+            Entity::WaterHeater(entity) => entity.device = device,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt::common::{AvailabilityCheck, Encoding, Nullable, Qos};
+
+    #[test]
+    fn kind_matches_variant() {
+        let cases = vec![
+            (
+                Entity::AlarmControlPanel(AlarmControlPanel::default()),
+                EntityKind::AlarmControlPanel,
+                "alarm_control_panel",
+            ),
+            (
+                Entity::BinarySensor(BinarySensor::default()),
+                EntityKind::BinarySensor,
+                "binary_sensor",
+            ),
+            (
+                Entity::Button(Button::default()),
+                EntityKind::Button,
+                "button",
+            ),
+            (
+                Entity::Camera(Camera::default()),
+                EntityKind::Camera,
+                "camera",
+            ),
+            (
+                Entity::Climate(Climate::default()),
+                EntityKind::Climate,
+                "climate",
+            ),
+            (Entity::Cover(Cover::default()), EntityKind::Cover, "cover"),
+            (
+                Entity::DeviceTracker(DeviceTracker::default()),
+                EntityKind::DeviceTracker,
+                "device_tracker",
+            ),
+            (
+                Entity::DeviceTrigger(DeviceTrigger::default()),
+                EntityKind::DeviceTrigger,
+                "device_trigger",
+            ),
+            (Entity::Event(Event::default()), EntityKind::Event, "event"),
+            (Entity::Fan(Fan::default()), EntityKind::Fan, "fan"),
+            (
+                Entity::Humidifier(Humidifier::default()),
+                EntityKind::Humidifier,
+                "humidifier",
+            ),
+            (Entity::Image(Image::default()), EntityKind::Image, "image"),
+            (
+                Entity::LawnMower(LawnMower::default()),
+                EntityKind::LawnMower,
+                "lawn_mower",
+            ),
+            (Entity::Light(Light::default()), EntityKind::Light, "light"),
+            (Entity::Lock(Lock::default()), EntityKind::Lock, "lock"),
+            (
+                Entity::Notify(Notify::default()),
+                EntityKind::Notify,
+                "notify",
+            ),
+            (
+                Entity::Number(Number::default()),
+                EntityKind::Number,
+                "number",
+            ),
+            (Entity::Scene(Scene::default()), EntityKind::Scene, "scene"),
+            (
+                Entity::Select(Select::default()),
+                EntityKind::Select,
+                "select",
+            ),
+            (
+                Entity::Sensor(Sensor::default()),
+                EntityKind::Sensor,
+                "sensor",
+            ),
+            (Entity::Siren(Siren::default()), EntityKind::Siren, "siren"),
+            (
+                Entity::Switch(Switch::default()),
+                EntityKind::Switch,
+                "switch",
+            ),
+            (Entity::Tag(Tag::default()), EntityKind::Tag, "tag"),
+            (Entity::Text(Text::default()), EntityKind::Text, "text"),
+            (
+                Entity::Update(Update::default()),
+                EntityKind::Update,
+                "update",
+            ),
+            (
+                Entity::Vacuum(Vacuum::default()),
+                EntityKind::Vacuum,
+                "vacuum",
+            ),
+            (Entity::Valve(Valve::default()), EntityKind::Valve, "valve"),
+            (
+                Entity::WaterHeater(WaterHeater::default()),
+                EntityKind::WaterHeater,
+                "water_heater",
+            ),
+        ];
+        for (entity, kind, component_name) in cases {
+            assert_eq!(kind, entity.kind());
+            assert_eq!(component_name, entity.kind().component_name());
+            assert_eq!(component_name, entity.get_component_name());
+        }
+    }
+
+    #[test]
+    fn slugifies_names_with_spaces() {
+        assert_eq!(
+            "living_room_temperature",
+            slugify_object_id("Living Room  Temperature")
+        );
+        assert_eq!("co2_level", slugify_object_id(" CO2 level! "));
+    }
+
+    #[test]
+    fn slugifies_unicode_names() {
+        assert_eq!("cafe_temperature", slugify_object_id("Café Température"));
+        assert_eq!("strasse", slugify_object_id("Straße"));
+        assert_eq!("unknown", slugify_object_id("温度"));
+    }
+
+    #[test]
+    fn suggests_object_id_from_name_or_unique_id() {
+        let entity = Entity::Number(Number::default().name("Target Temp").unique_id("abc-1"));
+        assert_eq!(
+            Some("target_temp".to_string()),
+            entity.suggested_object_id()
+        );
+        let entity = Entity::Number(Number::default().unique_id("abc-1"));
+        assert_eq!(Some("abc_1".to_string()), entity.suggested_object_id());
+        assert_eq!(
+            None,
+            Entity::Number(Number::default()).suggested_object_id()
+        );
+    }
+
+    #[test]
+    fn apply_defaults_keeps_set_fields() {
+        let mut entity = Entity::Number(Number::default().retain(false));
+        entity.apply_defaults(&MqttDefaults {
+            qos: Some(Qos::AtLeastOnce),
+            retain: Some(true),
+            encoding: Some(Encoding::Ascii),
+        });
+        let Entity::Number(number) = &entity else {
+            unreachable!();
+        };
+        assert_eq!(Some(false), number.retain);
+        assert_eq!(Some(Qos::AtLeastOnce), number.qos);
+        assert_eq!(Some(Encoding::Ascii), number.encoding);
+    }
+
+    #[test]
+    fn apply_defaults_converts_string_encoding() {
+        let mut entity = Entity::Sensor(Sensor::default());
+        entity.apply_defaults(&MqttDefaults {
+            encoding: Some(Encoding::Raw),
+            ..Default::default()
+        });
+        let Entity::Sensor(sensor) = &entity else {
+            unreachable!();
+        };
+        assert_eq!(Some(String::new()), sensor.encoding);
+        assert_eq!(None, sensor.qos);
+    }
+
+    #[test]
+    fn set_device_writes_through_to_variant() {
+        let mut entity = Entity::Number(Number::default());
+        entity.set_device(Device::default().add_identifier("thermostat"));
+        let Entity::Number(number) = &entity else {
+            unreachable!();
+        };
+        assert_eq!(vec!["thermostat".to_string()], number.device.identifiers);
+    }
+
+    #[test]
+    fn validate_rejects_invalid_topics() {
+        let entity = Entity::Number(Number::default().command_topic("home/\0/set"));
+        assert!(entity.validate().is_err());
+        let entity = Entity::Number(
+            Number::default()
+                .command_topic("~/set")
+                .availability(Availability::single_topic("home/\u{7}")),
+        );
+        assert!(entity.validate().is_err());
+        let entity = Entity::Number(Number::default().command_topic("~/set").object_id("a b"));
+        assert!(entity.validate().is_err());
+        let entity = Entity::Number(
+            Number::default()
+                .command_topic("home/thermostat/set")
+                .state_topic("home/thermostat"),
+        );
+        assert!(entity.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_wildcard_in_publish_topics() {
+        let vacuum = Vacuum::default().set_fan_speed_topic("vacuum/+/set_fan_speed");
+        assert!(Entity::Vacuum(vacuum).validate().is_err());
+        let mower = LawnMower::default().dock_command_topic("mower/#");
+        assert!(Entity::LawnMower(mower).validate().is_err());
+        let vacuum = Vacuum::default()
+            .set_fan_speed_topic("vacuum/set_fan_speed")
+            .state_topic("vacuum/+/state");
+        assert!(Entity::Vacuum(vacuum).validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_json_attributes_template_without_topic() {
+        let switch = Switch::default().json_attributes_template("{{ value_json }}");
+        assert!(Entity::Switch(switch.clone()).validate().is_err());
+        let switch = switch.json_attributes_topic("~/attributes");
+        assert!(Entity::Switch(switch).validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_invalid_configuration_url() {
+        let device = Device::default().configuration_url("ftp://device.local");
+        let entity = Entity::Switch(Switch::default().device(device));
+        assert!(entity.validate().is_err());
+        assert_eq!(1, validate_all(&[entity]).len());
+        let device = Device::default().configuration_url("https://device.local");
+        assert!(
+            Entity::Switch(Switch::default().device(device))
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn validate_rejects_zero_expire_after() {
+        let entity = Entity::Number(
+            Number::default().availability(Availability::single_topic("~/avty").expire_after(0)),
+        );
+        assert!(entity.validate().is_err());
+    }
+
+    #[test]
+    fn validate_all_reports_only_invalid_entities() {
+        let entities = vec![
+            Entity::LawnMower(LawnMower::default().dock_command_topic("~/dock")),
+            Entity::LawnMower(LawnMower::default()),
+            Entity::Sensor(Sensor::default()),
+            Entity::Sensor(Sensor::default().options(vec!["idle"])),
+        ];
+        let invalid: Vec<usize> = validate_all(&entities)
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(vec![1, 3], invalid);
+    }
+
+    #[test]
+    fn check_unique_ids_reports_duplicates() {
+        let entities = vec![
+            Entity::Sensor(Sensor::default().unique_id("temperature")),
+            Entity::Sensor(Sensor::default().unique_id("humidity")),
+            Entity::Number(Number::default().unique_id("temperature")),
+            Entity::Sensor(Sensor::default()),
+            Entity::Sensor(Sensor::default()),
+        ];
+        assert_eq!(
+            Err(vec!["temperature".to_string()]),
+            check_unique_ids(&entities)
+        );
+        assert_eq!(Ok(()), check_unique_ids(&entities[..2]));
+    }
+
+    #[test]
+    fn ordered_json_is_stable() {
+        let number = Entity::Number(
+            Number::default()
+                .unique_id("threshold")
+                .command_topic("~/set")
+                .name("Threshold")
+                .availability_topic("~/availability"),
+        );
+        let first = number.to_ordered_json().unwrap();
+        let second = number.clone().to_ordered_json().unwrap();
+        assert_eq!(first.as_bytes(), second.as_bytes());
+        assert!(first.find("\"cmd_t\"").unwrap() < first.find("\"name\"").unwrap());
+    }
+
+    #[test]
+    fn sort_keys_sorts_nested_objects() {
+        let value = sort_keys(serde_json::json!({
+            "b": [{ "d": 1, "c": 2 }],
+            "a": { "f": 1, "e": 2 }
+        }));
+        assert_eq!(
+            r#"{"a":{"e":2,"f":1},"b":[{"c":2,"d":1}]}"#,
+            serde_json::to_string(&value).unwrap()
+        );
+    }
+
+    #[test]
+    fn config_hash_changes_with_configuration() {
+        let number = Number::default()
+            .unique_id("threshold")
+            .command_topic("~/set");
+        let hash = Entity::Number(number.clone()).config_hash();
+        assert_eq!(hash, Entity::Number(number.clone()).config_hash());
+        assert_ne!(hash, Entity::Number(number.icon("mdi:gauge")).config_hash());
+    }
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        assert_eq!(0xcbf2_9ce4_8422_2325, fnv1a(b""));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, fnv1a(b"a"));
+        assert_eq!(0x8594_4171_f739_67e8, fnv1a(b"foobar"));
+    }
+
+    #[test]
+    fn removal_message_targets_discovery_topic() {
+        let cover = Entity::Cover(Cover::default().unique_id("garage_door"));
+        assert_eq!(
+            Some((
+                "homeassistant/cover/garage_door/config".to_string(),
+                String::new()
+            )),
+            cover.removal_message("homeassistant/", None)
+        );
+        assert_eq!(
+            Some((
+                "homeassistant/cover/garage/garage_door/config".to_string(),
+                String::new()
+            )),
+            cover.removal_message("homeassistant", Some("garage"))
+        );
+        assert_eq!(
+            None,
+            Entity::Cover(Cover::default()).removal_message("homeassistant", None)
+        );
+    }
+
+    #[test]
+    fn can_parse_entity_from_json() {
+        let entity = Entity::from_json(serde_json::json!({
+            "platform": "number",
+            "cmd_t": "~/set",
+            "uniq_id": "threshold",
+            "avty_mode": "latest",
+            "unknown": true
+        }))
+        .unwrap();
+        assert_eq!(Some("threshold"), entity.unique_id());
+    }
+
+    #[test]
+    fn strict_parsing_rejects_unknown_attribute() {
+        let config = serde_json::json!({
+            "platform": "number",
+            "cmd_t": "~/set",
+            "uniq_id": "threshold",
+            "avty_mode": "latest",
+            "dev": { "ids": ["thermostat"], "mdel": "typo" }
+        });
+        let error = Entity::from_strict_json(config).err().unwrap();
+        assert_eq!("unknown attributes: dev.mdel", error.to_string());
+    }
+
+    #[test]
+    fn strict_parsing_accepts_known_attributes() {
+        let config = serde_json::json!({
+            "platform": "number",
+            "cmd_t": "~/set",
+            "uniq_id": "threshold",
+            "avty_mode": "latest",
+            "dev": { "ids": ["thermostat"], "mdl": "model" },
+            "o": { "name": "origin" }
+        });
+        assert!(Entity::from_strict_json(config).is_ok());
+    }
+
+    #[test]
+    fn bounded_json_rejects_oversized_payloads() {
+        let entity: Entity = Switch::default().unique_id("switch").into();
+        let size = entity.to_ordered_json().unwrap().len();
+        assert_eq!(
+            entity.to_ordered_json().unwrap(),
+            entity.to_bounded_json(size).unwrap()
+        );
+        assert_eq!(
+            Err(PayloadTooLarge {
+                size,
+                max_bytes: size - 1
+            }),
+            entity.to_bounded_json(size - 1)
+        );
+    }
+
+    #[test]
+    fn lists_required_fields() {
+        assert_eq!(
+            &["command_topic"],
+            Entity::Number(Number::default()).required_fields()
+        );
+        assert_eq!(
+            &["command_topic", "options"],
+            EntityKind::Select.required_fields()
+        );
+        assert!(EntityKind::Cover.required_fields().is_empty());
+    }
+
+    #[test]
+    fn splits_oversized_attributes() {
+        let attributes = serde_json::json!({
+            "first": "a".repeat(20),
+            "second": "b".repeat(20),
+        });
+        let attributes = attributes.as_object().unwrap();
+        let chunks = split_attributes(attributes, 40).unwrap();
+        assert_eq!(
+            vec![
+                serde_json::json!({ "first": "a".repeat(20) }),
+                serde_json::json!({ "second": "b".repeat(20) }),
+            ],
+            chunks
+        );
+        for chunk in &chunks {
+            assert!(serde_json::to_string(chunk).unwrap().len() <= 40);
+        }
+        let whole = serde_json::to_string(attributes).unwrap().len();
+        assert_eq!(1, split_attributes(attributes, whole).unwrap().len());
+        assert!(split_attributes(attributes, 20).is_err());
+    }
+
+    #[test]
+    fn creates_default_entity_by_component_name() {
+        let entity = Entity::default_for_component("cover").unwrap();
+        assert_eq!(EntityKind::Cover, entity.kind());
+        assert_eq!(
+            Entity::from(Cover::default()).to_ordered_json().unwrap(),
+            entity.to_ordered_json().unwrap()
+        );
+        assert!(Entity::default_for_component("spaceship").is_none());
+    }
+
+    #[test]
+    fn default_entities_omit_empty_origin_and_device() {
+        for component in [
+            "alarm_control_panel",
+            "binary_sensor",
+            "button",
+            "camera",
+            "climate",
+            "cover",
+            "device_tracker",
+            "device_trigger",
+            "event",
+            "fan",
+            "humidifier",
+            "image",
+            "lawn_mower",
+            "light",
+            "lock",
+            "notify",
+            "number",
+            "scene",
+            "select",
+            "sensor",
+            "siren",
+            "switch",
+            "tag",
+            "text",
+            "update",
+            "vacuum",
+            "valve",
+            "water_heater",
+        ] {
+            let entity = Entity::default_for_component(component).unwrap();
+            let json = entity.get_attributes().unwrap();
+            assert!(json.get("o").is_none(), "{component}");
+            assert!(json.get("dev").is_none(), "{component}");
+        }
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn can_parse_cover_yaml() {
+        let yaml = r#"
+name: "MQTT Cover"
+command_topic: "living-room-cover/set"
+state_topic: "living-room-cover/state"
+payload_open: "OPEN"
+device:
+  identifiers:
+    - "cover-01"
+  manufacturer: "Acme"
+availability:
+  - topic: "living-room-cover/availability"
+"#;
+        let Entity::Cover(cover) = Entity::from_yaml("cover", yaml).unwrap() else {
+            panic!("expected a cover");
+        };
+        assert_eq!(Some("MQTT Cover".to_string()), cover.name);
+        assert_eq!(
+            Some("living-room-cover/set".to_string()),
+            cover.command_topic
+        );
+        assert_eq!(
+            Some("living-room-cover/state".to_string()),
+            cover.state_topic
+        );
+        assert_eq!(Nullable::Value("OPEN".to_string()), cover.payload_open);
+        assert_eq!(Some("Acme".to_string()), cover.device.manufacturer);
+        assert_eq!(
+            "living-room-cover/availability",
+            cover.availability.availability[0].topic
+        );
+        assert!(Entity::from_yaml("spaceship", yaml).is_err());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn can_render_number_yaml() {
+        let number = Number::default()
+            .name("Target temperature")
+            .command_topic("thermostat/target/set")
+            .state_topic("thermostat/target")
+            .device(Device::default().manufacturer("Acme"));
+        let yaml = Entity::from(number).to_yaml();
+        assert!(yaml.contains("command_topic: thermostat/target/set\n"));
+        assert!(yaml.contains("state_topic: thermostat/target\n"));
+        assert!(yaml.contains("device:\n  manufacturer: Acme\n"));
+        assert!(!yaml.contains("platform"));
+        let Entity::Number(parsed) = Entity::from_yaml("number", &yaml).unwrap() else {
+            panic!("expected a number");
+        };
+        assert_eq!("thermostat/target/set", parsed.command_topic);
+    }
+
+    #[test]
+    fn lists_template_fields() {
+        let cover = Cover::default()
+            .position_topic("home/cover/position")
+            .position_template("{{ value.y }}")
+            .tilt_status_topic("home/cover/tilt")
+            .tilt_status_template("{{ value_json.tilt }}")
+            .availability(Availability::single(
+                AvailabilityCheck::topic("home/cover/status").value_template("{{ value.up }}"),
+            ));
+        assert_eq!(
+            vec![
+                ("availability.value_template", "{{ value.up }}"),
+                ("position_template", "{{ value.y }}"),
+                ("tilt_status_template", "{{ value_json.tilt }}"),
+            ],
+            Entity::from(cover).template_fields()
+        );
+    }
 }
@@ -0,0 +1,49 @@
+/// Generates a builder setter for an optional field of an entity.
+///
+/// `String` fields accept anything convertible into a `String`, as do fields declared as `Into<Type>`
+/// for their `Type`. Other types are taken as is.
+/// The doc comment of the setter is given before the field name.
+macro_rules! builder_field {
+    ($(#[$meta:meta])* $field:ident: String) => {
+        $(#[$meta])*
+        pub fn $field<T: Into<String>>(mut self, $field: T) -> Self {
+            self.$field = Some($field.into());
+            self
+        }
+    };
+    ($(#[$meta:meta])* $field:ident: Into<$type:ty>) => {
+        $(#[$meta])*
+        pub fn $field<T: Into<$type>>(mut self, $field: T) -> Self {
+            self.$field = Some($field.into());
+            self
+        }
+    };
+    ($(#[$meta:meta])* $field:ident: $type:ty) => {
+        $(#[$meta])*
+        pub fn $field(mut self, $field: $type) -> Self {
+            self.$field = Some($field);
+            self
+        }
+    };
+}
+
+/// Lists the templates set among the given `Option<String>` fields of an entity as pairs of the field name
+/// and the template, e.g. `template_fields!(cover, position_template, tilt_status_template)`.
+macro_rules! template_fields {
+    ($entity:expr, $($field:ident),+ $(,)?) => {
+        [$((stringify!($field), $entity.$field.as_deref())),+]
+            .into_iter()
+            .filter_map(|(name, template)| Some((name, template?)))
+            .collect::<Vec<_>>()
+    };
+}
+
+/// Builds an [`Origin`](crate::mqtt::common::Origin) named after the crate invoking the macro,
+/// with its version as `sw_version`, e.g. `let origin = ha_mqtt_discovery::origin_from_crate!();`.
+#[macro_export]
+macro_rules! origin_from_crate {
+    () => {
+        $crate::mqtt::common::Origin::new(env!("CARGO_PKG_NAME"))
+            .with_sw_version(env!("CARGO_PKG_VERSION"))
+    };
+}
@@ -1,7 +1,7 @@
 use super::common::Qos;
 use super::common::{Availability, Device, EntityCategory, Origin};
 use crate::Entity;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Alarm control panel"
@@ -122,7 +122,8 @@ use serde_derive::Serialize;
 /// When your MQTT connection is not secured, this will send your secret code over the network unprotected!
 ///  
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AlarmControlPanel {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -130,11 +131,11 @@ pub struct AlarmControlPanel {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
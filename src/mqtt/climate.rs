@@ -1,9 +1,11 @@
 use super::common::Qos;
 use super::common::TemperatureUnit;
+use super::common::validate_preset_modes;
 use super::common::{Availability, Device, EntityCategory, Origin};
-use crate::Entity;
+use crate::{Entity, validate_topics};
+use anyhow::{Result, bail};
 pub use rust_decimal::Decimal;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT HVAC"
@@ -100,7 +102,8 @@ use serde_derive::Serialize;
 /// ```
 ///
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Climate {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -108,11 +111,11 @@ pub struct Climate {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -181,7 +184,7 @@ pub struct Climate {
     #[serde(rename = "fan_mode_stat_t", skip_serializing_if = "Option::is_none")]
     pub fan_mode_state_topic: Option<String>,
 
-    /// A list of supported fan modes.
+    /// A list of supported fan modes. (optional, default: `["auto", "low", "medium", "high"]`)
     #[serde(rename = "fan_modes", skip_serializing_if = "Option::is_none")]
     pub fan_modes: Option<Vec<String>>,
 
@@ -357,7 +360,7 @@ pub struct Climate {
     #[serde(rename = "swing_mode_stat_t", skip_serializing_if = "Option::is_none")]
     pub swing_mode_state_topic: Option<String>,
 
-    /// A list of supported swing modes.
+    /// A list of supported swing modes. (optional, default: `["on", "off"]`)
     #[serde(rename = "swing_modes", skip_serializing_if = "Option::is_none")]
     pub swing_modes: Option<Vec<String>>,
 
@@ -1096,3 +1099,182 @@ impl From<Climate> for Entity {
         Entity::Climate(value)
     }
 }
+
+impl Climate {
+    /// Checks that the temperature range is ordered (`min_temp < max_temp`), that `temp_step` is positive,
+    /// and that `preset_modes` are unique and don't include the reserved `none` preset.
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        if let (Some(min_temp), Some(max_temp)) = (self.min_temp, self.max_temp)
+            && min_temp >= max_temp
+        {
+            bail!("min_temp ({min_temp}) must be lower than max_temp ({max_temp})");
+        }
+        if let Some(temp_step) = self.temp_step
+            && temp_step <= Decimal::ZERO
+        {
+            bail!("temp_step ({temp_step}) must be positive");
+        }
+        validate_preset_modes(self.preset_modes.as_deref())?;
+        if let Some(preset_modes) = &self.preset_modes {
+            for (index, preset_mode) in preset_modes.iter().enumerate() {
+                if preset_modes[..index].contains(preset_mode) {
+                    bail!("duplicate preset mode '{preset_mode}'");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports configuration that is valid but likely a mistake, e.g. a Fahrenheit range given in Celsius.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.temperature_unit == Some(TemperatureUnit::Fahrenheit)
+            && let Some(max_temp) = self.max_temp
+            && max_temp < Decimal::from(50)
+        {
+            warnings.push(format!(
+                "max_temp ({max_temp}) looks like Celsius while temperature_unit is F"
+            ));
+        }
+        warnings
+    }
+
+    /// Returns `fan_modes`, or Home Assistant's default list when it's not set.
+    pub fn effective_fan_modes(&self) -> Vec<&str> {
+        match &self.fan_modes {
+            Some(fan_modes) => fan_modes.iter().map(String::as_str).collect(),
+            None => DEFAULT_FAN_MODES.to_vec(),
+        }
+    }
+
+    /// Returns `swing_modes`, or Home Assistant's default list when it's not set.
+    pub fn effective_swing_modes(&self) -> Vec<&str> {
+        match &self.swing_modes {
+            Some(swing_modes) => swing_modes.iter().map(String::as_str).collect(),
+            None => DEFAULT_SWING_MODES.to_vec(),
+        }
+    }
+}
+
+/// The current action of a climate device (its `hvac_action`), as reported on `action_topic`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClimateAction {
+    Off,
+    Heating,
+    Cooling,
+    Drying,
+    Idle,
+    Fan,
+}
+
+impl Climate {
+    /// Interprets a payload received on `action_topic`, after `action_template` is applied.
+    /// Payloads other than `off`, `heating`, `cooling`, `drying`, `idle` and `fan` yield `None`.
+    pub fn interpret_action(&self, payload: &str) -> Option<ClimateAction> {
+        match payload {
+            "off" => Some(ClimateAction::Off),
+            "heating" => Some(ClimateAction::Heating),
+            "cooling" => Some(ClimateAction::Cooling),
+            "drying" => Some(ClimateAction::Drying),
+            "idle" => Some(ClimateAction::Idle),
+            "fan" => Some(ClimateAction::Fan),
+            _ => None,
+        }
+    }
+}
+
+/// The fan modes Home Assistant uses when `fan_modes` isn't set.
+pub const DEFAULT_FAN_MODES: [&str; 4] = ["auto", "low", "medium", "high"];
+
+/// The swing modes Home Assistant uses when `swing_modes` isn't set.
+pub const DEFAULT_SWING_MODES: [&str; 2] = ["on", "off"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn effective_modes_default_to_home_assistant_lists() {
+        let climate = Climate::default();
+        assert_eq!(
+            vec!["auto", "low", "medium", "high"],
+            climate.effective_fan_modes()
+        );
+        assert_eq!(vec!["on", "off"], climate.effective_swing_modes());
+    }
+
+    #[test]
+    fn effective_modes_use_explicit_lists() {
+        let climate = Climate::default()
+            .fan_modes(vec!["quiet", "turbo"])
+            .swing_modes(vec!["vertical"]);
+        assert_eq!(vec!["quiet", "turbo"], climate.effective_fan_modes());
+        assert_eq!(vec!["vertical"], climate.effective_swing_modes());
+    }
+
+    #[test]
+    fn accepts_ordered_temperature_range() {
+        let climate = Climate::default()
+            .min_temp(dec!(7))
+            .max_temp(dec!(35))
+            .temp_step(dec!(0.5));
+        assert!(climate.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_inverted_temperature_range() {
+        let climate = Climate::default().min_temp(dec!(35)).max_temp(dec!(7));
+        assert!(climate.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_empty_temperature_range() {
+        let climate = Climate::default().min_temp(dec!(20)).max_temp(dec!(20));
+        assert!(climate.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_non_positive_temp_step() {
+        assert!(Climate::default().temp_step(dec!(0)).validate().is_err());
+    }
+
+    #[test]
+    fn warns_about_celsius_range_in_fahrenheit() {
+        let climate = Climate::default()
+            .temperature_unit(TemperatureUnit::Fahrenheit)
+            .min_temp(dec!(7))
+            .max_temp(dec!(35));
+        assert!(climate.validate().is_ok());
+        assert_eq!(1, climate.warnings().len());
+    }
+
+    #[test]
+    fn rejects_reserved_preset_mode() {
+        let climate = Climate::default().preset_modes(vec!["eco", "none"]);
+        assert!(climate.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_preset_modes() {
+        let climate = Climate::default().preset_modes(vec!["eco", "away", "eco"]);
+        assert!(climate.validate().is_err());
+    }
+
+    #[test]
+    fn interprets_actions() {
+        let climate = Climate::default();
+        for (payload, action) in [
+            ("off", ClimateAction::Off),
+            ("heating", ClimateAction::Heating),
+            ("cooling", ClimateAction::Cooling),
+            ("drying", ClimateAction::Drying),
+            ("idle", ClimateAction::Idle),
+            ("fan", ClimateAction::Fan),
+        ] {
+            assert_eq!(Some(action), climate.interpret_action(payload));
+        }
+        assert_eq!(None, climate.interpret_action("humidifying"));
+    }
+}
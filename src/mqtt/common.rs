@@ -1,9 +1,12 @@
+use crate::Entity;
+use anyhow::{anyhow, bail};
 use serde::ser::SerializeSeq;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
 
 /// Classification of a non-primary entity.
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EntityCategory {
     /// The entity allows changing the configuration of a device,
     /// for example a switch entity making it possible to turn the background illumination of a switch on and off.
@@ -17,7 +20,7 @@ pub enum EntityCategory {
 }
 
 /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-#[derive(Clone, Debug, PartialEq, Serialize, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct Origin {
     /// The name of the application that is the origin the discovered MQTT item. This option is required.
     #[serde(rename = "name")]
@@ -31,6 +34,11 @@ pub struct Origin {
 }
 
 impl Origin {
+    /// Whether no origin information is set, not even the name.
+    pub fn is_empty(&self) -> bool {
+        *self == Origin::default()
+    }
+
     /// The name of the application that is the origin the discovered MQTT item. This option is required.
     pub fn new<S: Into<String>>(name: S) -> Self {
         Origin {
@@ -39,6 +47,12 @@ impl Origin {
         }
     }
 
+    /// An origin named after this crate, with its version. Use [`origin_from_crate!`](crate::origin_from_crate)
+    /// to name the origin after the application instead.
+    pub fn from_crate() -> Self {
+        crate::origin_from_crate!()
+    }
+
     /// Software version of the application that supplies the discovered MQTT item.
     pub fn with_sw_version<S: Into<String>>(mut self, sw_version: S) -> Self {
         self.sw_version = Some(sw_version.into());
@@ -53,7 +67,8 @@ impl Origin {
 }
 
 /// Information about the device this sensor is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/device_registry_index/). Only works when `unique_id` is set. At least one of identifiers or connections must be present to identify the device.
-#[derive(Clone, Debug, PartialEq, Serialize, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Device {
     /// The name of the device.
     #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
@@ -73,6 +88,12 @@ pub struct Device {
     /// The model of the device.
     #[serde(rename = "mdl", skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
+    /// The model identifier of the device.
+    #[serde(rename = "mdl_id", skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    /// The serial number of the device.
+    #[serde(rename = "sn", skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
     /// Suggest an area if the device isn’t in one yet.
     #[serde(rename = "sa", skip_serializing_if = "Option::is_none")]
     pub suggested_area: Option<String>,
@@ -88,6 +109,11 @@ pub struct Device {
 }
 
 impl Device {
+    /// Whether no device information is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Device::default()
+    }
+
     /// The name of the device.
     pub fn name<S: Into<String>>(mut self, name: S) -> Self {
         self.name = Some(name.into());
@@ -106,12 +132,42 @@ impl Device {
         self
     }
 
+    /// A device identified by the given connections, e.g. `[(ConnectionType::Mac, mac_address)]`.
+    pub fn with_connections<I: IntoIterator<Item = (ConnectionType, String)>>(
+        connections: I,
+    ) -> Self {
+        Self {
+            connections: connections
+                .into_iter()
+                .map(|(r#type, identifier)| DeviceConnection::new(r#type, identifier))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
     /// A link to the webpage that can manage the configuration of this device. Can be either an `http://`, `https://` or an internal `homeassistant://` URL.
     pub fn configuration_url<S: Into<String>>(mut self, configuration_url: S) -> Self {
         self.configuration_url = Some(configuration_url.into());
         self
     }
 
+    /// Sets `configuration_url` to an internal `homeassistant://` link to `path`,
+    /// e.g. `navigate/config/integrations` becomes `homeassistant://navigate/config/integrations`.
+    ///
+    /// A path already starting with `homeassistant://` is kept as is. Fails for an empty path
+    /// or a URL with any other scheme.
+    pub fn configuration_deep_link(self, path: &str) -> anyhow::Result<Self> {
+        let path = path.strip_prefix("homeassistant://").unwrap_or(path);
+        let path = path.trim_start_matches('/');
+        if path.is_empty() {
+            bail!("deep link path is empty");
+        }
+        if path.contains("://") {
+            bail!("deep link path '{path}' should not have a scheme other than homeassistant://");
+        }
+        Ok(self.configuration_url(format!("homeassistant://{path}")))
+    }
+
     /// The manufacturer of the device.
     pub fn manufacturer<S: Into<String>>(mut self, manufacturer: S) -> Self {
         self.manufacturer = Some(manufacturer.into());
@@ -124,6 +180,18 @@ impl Device {
         self
     }
 
+    /// The model identifier of the device.
+    pub fn model_id<S: Into<String>>(mut self, model_id: S) -> Self {
+        self.model_id = Some(model_id.into());
+        self
+    }
+
+    /// The serial number of the device.
+    pub fn serial_number<S: Into<String>>(mut self, serial_number: S) -> Self {
+        self.serial_number = Some(serial_number.into());
+        self
+    }
+
     /// Suggest an area if the device isn’t in one yet.
     pub fn suggested_area<S: Into<String>>(mut self, suggested_area: S) -> Self {
         self.suggested_area = Some(suggested_area.into());
@@ -147,6 +215,59 @@ impl Device {
         self.via_device = Some(via_device.into());
         self
     }
+
+    /// Routes the messages of the device via the hub with the given identifier, setting `via_device`.
+    pub fn via<S: Into<String>>(self, hub_identifier: S) -> Self {
+        self.via_device(hub_identifier)
+    }
+}
+
+impl Device {
+    /// Checks that `configuration_url` uses one of the schemes Home Assistant accepts:
+    /// `http://`, `https://` or `homeassistant://`.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(configuration_url) = &self.configuration_url
+            && !["http://", "https://", "homeassistant://"]
+                .iter()
+                .any(|scheme| configuration_url.starts_with(scheme))
+        {
+            bail!(
+                "configuration_url '{configuration_url}' should use http, https or homeassistant scheme"
+            );
+        }
+        Ok(())
+    }
+
+    /// Makes every device in `sub_devices` route its messages via `hub`, using the first identifier of the hub.
+    ///
+    /// Fails if the hub has no identifier or if a sub-device already references a different hub,
+    /// in which case none of the sub-devices is changed.
+    pub fn route_via(hub: &Device, sub_devices: &mut [Device]) -> anyhow::Result<()> {
+        let hub_identifier = Self::hub_identifier(hub, sub_devices.iter())?;
+        for sub_device in sub_devices.iter_mut() {
+            sub_device.via_device = Some(hub_identifier.clone());
+        }
+        Ok(())
+    }
+
+    /// The first identifier of `hub`, checked to be usable as the `via_device` of every device in `sub_devices`.
+    pub(crate) fn hub_identifier<'a>(
+        hub: &Device,
+        sub_devices: impl IntoIterator<Item = &'a Device>,
+    ) -> anyhow::Result<String> {
+        let hub_identifier = hub
+            .identifiers
+            .first()
+            .ok_or(anyhow!("the hub device should have an identifier"))?;
+        for sub_device in sub_devices {
+            if let Some(via_device) = &sub_device.via_device
+                && via_device != hub_identifier
+            {
+                bail!("device is already routed via '{via_device}' instead of '{hub_identifier}'");
+            }
+        }
+        Ok(hub_identifier.clone())
+    }
 }
 
 /// A tuple `[connection_type, connection_identifier]`.
@@ -164,6 +285,48 @@ impl DeviceConnection {
             identifier: mac_address.into(),
         }
     }
+
+    /// A connection of the given type, e.g. a Zigbee IEEE address.
+    pub fn new<S: Into<String>>(r#type: ConnectionType, identifier: S) -> Self {
+        DeviceConnection {
+            r#type: r#type.into(),
+            identifier: identifier.into(),
+        }
+    }
+}
+
+/// The type of a [DeviceConnection], as known to the Home Assistant device registry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionType {
+    Mac,
+    Upnp,
+    Zigbee,
+    Bluetooth,
+    /// Any other connection type.
+    Other(String),
+}
+
+impl From<ConnectionType> for String {
+    fn from(value: ConnectionType) -> Self {
+        match value {
+            ConnectionType::Mac => "mac".to_string(),
+            ConnectionType::Upnp => "upnp".to_string(),
+            ConnectionType::Zigbee => "zigbee".to_string(),
+            ConnectionType::Bluetooth => "bluetooth".to_string(),
+            ConnectionType::Other(r#type) => r#type,
+        }
+    }
+}
+
+impl<'de> serde::de::Deserialize<'de> for DeviceConnection {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (r#type, identifier): (String, String) =
+            serde::de::Deserialize::deserialize(deserializer)?;
+        Ok(DeviceConnection { r#type, identifier })
+    }
 }
 
 impl serde::ser::Serialize for DeviceConnection {
@@ -178,9 +341,10 @@ impl serde::ser::Serialize for DeviceConnection {
     }
 }
 
+/// The state class of a [Sensor](crate::mqtt::sensor::Sensor) or a [Number](crate::mqtt::number::Number).
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
-pub enum SensorStateClass {
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StateClass {
     /// The state represents a measurement in present time, not a historical aggregation such as statistics or a prediction of the future.
     ///
     /// Examples of what should be classified measurement are: current temperature, humidify or electric power.
@@ -204,13 +368,21 @@ pub enum SensorStateClass {
     TotalIncreasing,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Default)]
+/// The former name of [StateClass], from when only sensors had a state class.
+pub type SensorStateClass = StateClass;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Availability {
     /// Controls the conditions needed to set the entity to `available`.
-    #[serde(rename = "avty_mode")]
+    /// Omitted when it's `latest`, the Home Assistant default.
+    #[serde(
+        rename = "avty_mode",
+        skip_serializing_if = "AvailabilityMode::is_default"
+    )]
     pub mode: AvailabilityMode,
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
-    #[serde(rename = "avty")]
+    #[serde(rename = "avty", skip_serializing_if = "Vec::is_empty")]
     pub availability: Vec<AvailabilityCheck>,
     /// If set, it defines the number of seconds after the sensor’s state expires, if it’s not updated.
     /// After expiry, the sensor’s state becomes unavailable. Default the sensors state never expires.
@@ -234,7 +406,7 @@ impl Availability {
     /// An availability checker using a single check.
     pub fn single(availability: AvailabilityCheck) -> Self {
         Self {
-            mode: AvailabilityMode::All,
+            mode: AvailabilityMode::default(),
             availability: vec![availability],
             expire_after: None,
         }
@@ -272,24 +444,80 @@ impl Availability {
         self.expire_after = Some(expire_after);
         self
     }
+
+    /// Sets `expire_after` from a [Duration](std::time::Duration).
+    /// Fails if the duration isn't a whole number of seconds, as Home Assistant only accepts seconds.
+    pub fn expire_after_duration(self, expire_after: std::time::Duration) -> anyhow::Result<Self> {
+        if expire_after.subsec_nanos() != 0 {
+            bail!("expire_after {expire_after:?} isn't a whole number of seconds");
+        }
+        Ok(self.expire_after(expire_after.as_secs()))
+    }
+
+    /// Checks that `expire_after`, if set, is at least 1 second, as Home Assistant ignores 0.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.expire_after == Some(0) {
+            bail!("expire_after must be at least 1 second");
+        }
+        Ok(())
+    }
+
+    /// Combines two availability configurations, e.g. a device-wide default with an entity-specific one.
+    ///
+    /// The checks of both configurations are unioned, skipping exact duplicates.
+    /// The stricter mode is kept: `all` wins over `latest`, which wins over `any`.
+    /// A configuration without checks doesn't take part in choosing the mode.
+    /// If both configurations set `expire_after`, the shorter one is kept.
+    pub fn merge(mut self, other: Availability) -> Self {
+        self.mode = if self.availability.is_empty() {
+            other.mode
+        } else if other.availability.is_empty() {
+            self.mode
+        } else {
+            match (self.mode, other.mode) {
+                (AvailabilityMode::All, _) | (_, AvailabilityMode::All) => AvailabilityMode::All,
+                (AvailabilityMode::Latest, _) | (_, AvailabilityMode::Latest) => {
+                    AvailabilityMode::Latest
+                }
+                _ => AvailabilityMode::Any,
+            }
+        };
+        for check in other.availability {
+            if !self.availability.contains(&check) {
+                self.availability.push(check);
+            }
+        }
+        self.expire_after = match (self.expire_after, other.expire_after) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub enum AvailabilityMode {
     /// `payload_available` must be received on all configured availability topics before the entity is marked as online.
     #[serde(rename = "all")]
-    #[default]
     All,
     /// `payload_available` must be received on at least one configured availability topic before the entity is marked as online.
     #[serde(rename = "any")]
     Any,
     /// the last `payload_available` or `payload_not_available` received on any configured availability topic controls the availability.
+    /// This is the Home Assistant default.
     #[serde(rename = "latest")]
+    #[default]
     Latest,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Default)]
+impl AvailabilityMode {
+    fn is_default(&self) -> bool {
+        *self == AvailabilityMode::default()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct AvailabilityCheck {
     /// The payload that represents the available state. (optional, default: `online`)
     #[serde(rename = "pl_avail", skip_serializing_if = "Option::is_none")]
@@ -331,11 +559,30 @@ impl AvailabilityCheck {
         self.value_template = Some(value_template.into());
         self
     }
+
+    /// Whether `payload_after_template`, the payload with `value_template` already applied, marks the entity
+    /// available, i.e. equals `payload_available` (default `online`).
+    /// The comparison is exact, so `Online` or `offline` yield `false`.
+    pub fn is_available(&self, payload_after_template: &str) -> bool {
+        payload_after_template == self.payload_available.as_deref().unwrap_or("online")
+    }
+}
+
+/// Broker-wide defaults for the MQTT attributes of entities, applied with
+/// [Entity::apply_defaults](crate::Entity::apply_defaults).
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct MqttDefaults {
+    /// The default QoS of the entities.
+    pub qos: Option<Qos>,
+    /// Whether the entities publish retained commands by default.
+    pub retain: Option<bool>,
+    /// The default encoding of the payloads.
+    pub encoding: Option<Encoding>,
 }
 
 /// The maximum QoS level to be used when receiving and publishing messages.
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Qos {
     /// At most once (QoS 0)
     #[serde(rename = "0")]
@@ -350,8 +597,146 @@ pub enum Qos {
     ExactlyOnce,
 }
 
+/// An optional attribute that can also be explicitly set to `null`, e.g. to override a value Home Assistant would infer.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum Nullable<T> {
+    /// The attribute is omitted and Home Assistant uses its default.
+    #[default]
+    Unset,
+    /// The attribute is serialized as `null`.
+    Null,
+    /// The attribute is serialized with the given value.
+    Value(T),
+}
+
+impl<T> Nullable<T> {
+    /// Whether the attribute is omitted, for `skip_serializing_if`.
+    pub fn is_unset(&self) -> bool {
+        matches!(self, Nullable::Unset)
+    }
+
+    /// The value of the attribute, if it's set to one.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Nullable::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T: serde::Serialize> serde::Serialize for Nullable<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Nullable::Value(value) => value.serialize(serializer),
+            _ => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'de, T: serde::de::Deserialize<'de>> serde::de::Deserialize<'de> for Nullable<T> {
+    /// A present attribute is either `null` or a value. Missing attributes need `#[serde(default)]`.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value: Option<T> = serde::de::Deserialize::deserialize(deserializer)?;
+        Ok(value.map_or(Nullable::Null, Nullable::Value))
+    }
+}
+
+/// The pair of payloads representing the `on` and `off` states or commands.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OnOffPayloads {
+    /// The payload representing the `on` state or command.
+    pub on: String,
+    /// The payload representing the `off` state or command.
+    pub off: String,
+}
+
+impl Default for OnOffPayloads {
+    /// The `ON` and `OFF` payloads Home Assistant uses by default.
+    fn default() -> Self {
+        Self::custom("ON", "OFF")
+    }
+}
+
+impl OnOffPayloads {
+    /// Custom payloads, e.g. `1` and `0`, replacing the default `ON` and `OFF`.
+    pub fn custom<On: Into<String>, Off: Into<String>>(on: On, off: Off) -> Self {
+        Self {
+            on: on.into(),
+            off: off.into(),
+        }
+    }
+}
+
+/// The QoS and retain flag an entity uses for its messages, with Home Assistant defaults applied.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublishOptions {
+    /// The maximum QoS level to be used when receiving and publishing messages. (default: `0`)
+    pub qos: Qos,
+    /// If the published message should have the retain flag on or not. (default: `false`)
+    pub retain: bool,
+}
+
+impl Default for PublishOptions {
+    fn default() -> Self {
+        Self {
+            qos: Qos::AtMostOnce,
+            retain: false,
+        }
+    }
+}
+
+impl PublishOptions {
+    /// Resolves the effective QoS and retain flag of the entity.
+    /// Entities that don't support these options always use the defaults.
+    pub fn for_entity(entity: &Entity) -> Self {
+        let (qos, retain) = match entity {
+            Entity::AlarmControlPanel(alarm_control_panel) => {
+                (alarm_control_panel.qos.clone(), alarm_control_panel.retain)
+            }
+            Entity::BinarySensor(binary_sensor) => (binary_sensor.qos.clone(), None),
+            Entity::Button(button) => (button.qos.clone(), button.retain),
+            Entity::Camera(_) => (None, None),
+            Entity::Climate(climate) => (climate.qos.clone(), climate.retain),
+            Entity::Cover(cover) => (cover.qos.clone(), cover.retain),
+            Entity::DeviceTracker(device_tracker) => (device_tracker.qos.clone(), None),
+            Entity::DeviceTrigger(device_trigger) => (device_trigger.qos.clone(), None),
+            Entity::Event(event) => (event.qos.clone(), None),
+            Entity::Fan(fan) => (fan.qos.clone(), fan.retain),
+            Entity::Humidifier(humidifier) => (humidifier.qos.clone(), humidifier.retain),
+            Entity::Image(_) => (None, None),
+            Entity::LawnMower(lawn_mower) => (lawn_mower.qos.clone(), lawn_mower.retain),
+            Entity::Light(light) => (light.qos.clone(), light.retain),
+            Entity::Lock(lock) => (lock.qos.clone(), lock.retain),
+            Entity::Notify(notify) => (notify.qos.clone(), notify.retain),
+            Entity::Number(number) => (number.qos.clone(), number.retain),
+            Entity::Scene(scene) => (scene.qos.clone(), scene.retain),
+            Entity::Select(select) => (select.qos.clone(), select.retain),
+            Entity::Sensor(sensor) => (sensor.qos.clone(), None),
+            Entity::Siren(siren) => (siren.qos.clone(), siren.retain),
+            Entity::Switch(switch) => (switch.qos.clone(), switch.retain),
+            Entity::Tag(_) => (None, None),
+            Entity::Text(text) => (text.qos.clone(), text.retain),
+            Entity::Update(update) => (update.qos.clone(), update.retain),
+            Entity::Vacuum(vacuum) => (vacuum.qos.clone(), vacuum.retain),
+            Entity::Valve(valve) => (valve.qos.clone(), valve.retain),
+            Entity::WaterHeater(water_heater) => (water_heater.qos.clone(), water_heater.retain),
+        };
+        let defaults = Self::default();
+        Self {
+            qos: qos.unwrap_or(defaults.qos),
+            retain: retain.unwrap_or(defaults.retain),
+        }
+    }
+}
+
 /// Defines the temperature unit of the device, `C` or `F`. If this is not set, the temperature unit is set to the system temperature unit.
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TemperatureUnit {
     #[serde(rename = "C")]
     Celcius,
@@ -360,13 +745,337 @@ pub enum TemperatureUnit {
     Fahrenheit,
 }
 
+/// The encoding of the payloads an entity receives and publishes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Encoding {
+    /// `utf-8`, the Home Assistant default.
+    Utf8,
+    /// `ascii`.
+    Ascii,
+    /// `""`: incoming payloads aren't decoded.
+    Raw,
+    /// Any other encoding supported by Python, e.g. `latin-1`.
+    Other(String),
+}
+
+impl From<String> for Encoding {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "utf-8" => Encoding::Utf8,
+            "ascii" => Encoding::Ascii,
+            "" => Encoding::Raw,
+            _ => Encoding::Other(value),
+        }
+    }
+}
+
+impl From<&str> for Encoding {
+    fn from(value: &str) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl From<Encoding> for String {
+    fn from(value: Encoding) -> Self {
+        match value {
+            Encoding::Utf8 => "utf-8".to_string(),
+            Encoding::Ascii => "ascii".to_string(),
+            Encoding::Raw => String::new(),
+            Encoding::Other(encoding) => encoding,
+        }
+    }
+}
+
+/// Returned when a topic or an object ID contains characters MQTT or Home Assistant don't accept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TopicError {
+    /// The topic is empty.
+    Empty,
+    /// The topic contains a null byte, which MQTT forbids.
+    NullByte,
+    /// The topic contains a control character other than the null byte.
+    ControlCharacter(char),
+    /// The object ID contains whitespace.
+    Whitespace,
+    /// A topic Home Assistant publishes to contains the `+` or `#` wildcard, which only subscriptions accept.
+    Wildcard,
+}
+
+impl fmt::Display for TopicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopicError::Empty => write!(f, "topic is empty"),
+            TopicError::NullByte => write!(f, "topic contains a null byte"),
+            TopicError::ControlCharacter(c) => {
+                write!(f, "topic contains the control character {c:?}")
+            }
+            TopicError::Whitespace => write!(f, "object ID contains whitespace"),
+            TopicError::Wildcard => write!(f, "topic published to contains a wildcard"),
+        }
+    }
+}
+
+impl std::error::Error for TopicError {}
+
+/// Checks that `topic` is non-empty and free of null bytes and other control characters.
+pub fn validate_topic(topic: &str) -> Result<(), TopicError> {
+    if topic.is_empty() {
+        return Err(TopicError::Empty);
+    }
+    match topic.chars().find(|c| c.is_control()) {
+        Some('\0') => Err(TopicError::NullByte),
+        Some(c) => Err(TopicError::ControlCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+/// Checks that `topic`, which Home Assistant publishes to (e.g. a `command_topic`), is a valid topic
+/// without the `+` and `#` wildcards. Topics Home Assistant subscribes to may contain them.
+pub fn validate_publish_topic(topic: &str) -> Result<(), TopicError> {
+    validate_topic(topic)?;
+    if topic.contains(['+', '#']) {
+        return Err(TopicError::Wildcard);
+    }
+    Ok(())
+}
+
+/// A valid MQTT topic, checked according to whether Home Assistant subscribes or publishes to it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Topic(String);
+
+impl Topic {
+    /// A topic Home Assistant subscribes to (e.g. a `state_topic`), which may contain the `+` and `#` wildcards.
+    pub fn subscribe<S: Into<String>>(topic: S) -> Result<Self, TopicError> {
+        let topic = topic.into();
+        validate_topic(&topic)?;
+        Ok(Self(topic))
+    }
+
+    /// A topic Home Assistant publishes to (e.g. a `command_topic`), which must not contain wildcards.
+    pub fn publish<S: Into<String>>(topic: S) -> Result<Self, TopicError> {
+        let topic = topic.into();
+        validate_publish_topic(&topic)?;
+        Ok(Self(topic))
+    }
+
+    /// The topic as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Topic {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Topic> for String {
+    fn from(topic: Topic) -> Self {
+        topic.0
+    }
+}
+
+/// Checks that `object_id`, which becomes a segment of the discovery topic, is a valid topic without whitespace.
+pub fn validate_object_id(object_id: &str) -> Result<(), TopicError> {
+    validate_topic(object_id)?;
+    if object_id.chars().any(char::is_whitespace) {
+        return Err(TopicError::Whitespace);
+    }
+    Ok(())
+}
+
+/// Checks that a `json_attributes_template` is only set along with the `json_attributes_topic` it applies to,
+/// given the serialized configuration of an entity.
+pub(crate) fn validate_json_attributes(attributes: &serde_json::Value) -> anyhow::Result<()> {
+    if attributes.get("json_attr_tpl").is_some() && attributes.get("json_attr_t").is_none() {
+        bail!("json_attributes_template requires json_attributes_topic");
+    }
+    Ok(())
+}
+
+/// Checks that `preset_modes` doesn't include `none`, which Home Assistant reserves for having no preset.
+/// Only the exact lowercase `none` is reserved.
+pub(crate) fn validate_preset_modes(preset_modes: Option<&[String]>) -> anyhow::Result<()> {
+    if preset_modes
+        .into_iter()
+        .flatten()
+        .any(|preset| preset == "none")
+    {
+        bail!("preset_modes must not include the reserved 'none' preset");
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::mqtt::{camera::Camera, number::Number};
     use assert_json_diff::assert_json_eq;
+    use serde_derive::Deserialize;
     use serde_json::json;
 
     use super::*;
 
+    #[test]
+    fn builds_device_from_connection_tuples() {
+        let device =
+            Device::with_connections([(ConnectionType::Mac, "02:5b:26:a8:dc:12".to_string())]);
+        assert_eq!(
+            vec![DeviceConnection::mac("02:5b:26:a8:dc:12")],
+            device.connections
+        );
+        assert_eq!(
+            json!({ "cns": [["mac", "02:5b:26:a8:dc:12"]] }),
+            serde_json::to_value(&device).unwrap()
+        );
+    }
+
+    #[test]
+    fn builds_configuration_deep_link() {
+        let device = Device::default()
+            .configuration_deep_link("/navigate/config/integrations")
+            .unwrap();
+        assert_eq!(
+            Some("homeassistant://navigate/config/integrations".to_string()),
+            device.configuration_url
+        );
+        assert!(device.validate().is_ok());
+
+        let device = Device::default()
+            .configuration_deep_link("homeassistant://navigate/lovelace")
+            .unwrap();
+        assert_eq!(
+            Some("homeassistant://navigate/lovelace".to_string()),
+            device.configuration_url
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_configuration_deep_link() {
+        assert!(Device::default().configuration_deep_link("").is_err());
+        assert!(
+            Device::default()
+                .configuration_deep_link("https://example.com")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn encoding_serialization() {
+        assert_eq!(
+            json!("utf-8"),
+            serde_json::to_value(Encoding::Utf8).unwrap()
+        );
+        assert_eq!(
+            json!("ascii"),
+            serde_json::to_value(Encoding::Ascii).unwrap()
+        );
+        assert_eq!(json!(""), serde_json::to_value(Encoding::Raw).unwrap());
+        assert_eq!(
+            json!("latin-1"),
+            serde_json::to_value(Encoding::Other("latin-1".to_string())).unwrap()
+        );
+        assert_eq!(
+            Encoding::Raw,
+            serde_json::from_value::<Encoding>(json!("")).unwrap()
+        );
+    }
+
+    #[test]
+    fn validates_topic_characters() {
+        assert_eq!(Ok(()), validate_topic("home/living room/temperature"));
+        assert_eq!(Err(TopicError::NullByte), validate_topic("home/\0/state"));
+        assert_eq!(
+            Err(TopicError::ControlCharacter('\n')),
+            validate_topic("home/\nstate")
+        );
+        assert_eq!(Err(TopicError::Empty), validate_topic(""));
+    }
+
+    #[test]
+    fn rejects_whitespace_in_object_id() {
+        assert_eq!(Ok(()), validate_object_id("living_room_temperature"));
+        assert_eq!(
+            Err(TopicError::Whitespace),
+            validate_object_id("living room")
+        );
+    }
+
+    #[test]
+    fn state_class_serialization() {
+        assert_eq!(
+            json!("measurement"),
+            serde_json::to_value(StateClass::Measurement).unwrap()
+        );
+        assert_eq!(
+            json!("total"),
+            serde_json::to_value(StateClass::Total).unwrap()
+        );
+        assert_eq!(
+            json!("total_increasing"),
+            serde_json::to_value(StateClass::TotalIncreasing).unwrap()
+        );
+    }
+
+    #[test]
+    fn availability_check_compares_to_default_payloads() {
+        let check = AvailabilityCheck::topic("~/status").value_template("{{ value_json.state }}");
+        assert!(check.is_available("online"));
+        assert!(!check.is_available("offline"));
+        assert!(!check.is_available("Online"));
+    }
+
+    #[test]
+    fn availability_check_compares_to_custom_payloads() {
+        let check = AvailabilityCheck::topic("~/status")
+            .value_template("{{ 'up' if value_json.alive else 'down' }}")
+            .payload_available("up")
+            .payload_not_available("down");
+        assert!(check.is_available("up"));
+        assert!(!check.is_available("down"));
+        assert!(!check.is_available("online"));
+    }
+
+    #[test]
+    fn default_origin_and_device_are_empty() {
+        assert!(Origin::default().is_empty());
+        assert!(Device::default().is_empty());
+        assert!(!Origin::new("app").is_empty());
+        assert!(!Device::default().add_identifier("id").is_empty());
+    }
+
+    #[test]
+    fn expire_after_duration_in_seconds() {
+        let availability = Availability::single_topic("~/availability")
+            .expire_after_duration(std::time::Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(60, serde_json::to_value(availability).unwrap()["exp_aft"]);
+    }
+
+    #[test]
+    fn expire_after_duration_rejects_sub_second() {
+        let availability = Availability::default();
+        assert!(
+            availability
+                .expire_after_duration(std::time::Duration::from_millis(1500))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_zero_expire_after() {
+        assert!(Availability::default().expire_after(0).validate().is_err());
+        assert!(Availability::default().expire_after(1).validate().is_ok());
+        assert!(Availability::default().validate().is_ok());
+    }
+
     #[test]
     fn can_serialize_origin() {
         let origin = Origin {
@@ -393,6 +1102,8 @@ mod tests {
             configuration_url: Some("http://config.url".to_string()),
             manufacturer: Some("device manufacturer".to_string()),
             model: Some("device model".to_string()),
+            model_id: Some("device model id".to_string()),
+            serial_number: Some("device serial number".to_string()),
             suggested_area: Some("area".to_string()),
             sw_version: Some("sw_v".to_string()),
             hw_version: Some("hw_v".to_string()),
@@ -414,6 +1125,8 @@ mod tests {
               "cu": "http://config.url",
               "mf": "device manufacturer",
               "mdl": "device model",
+              "mdl_id": "device model id",
+              "sn": "device serial number",
               "sa": "area",
               "sw": "sw_v",
               "hw": "hw_v",
@@ -422,4 +1135,210 @@ mod tests {
             serde_json::to_value(&device).unwrap()
         );
     }
+
+    #[test]
+    fn device_builder_sets_suggested_area_and_hw_version() {
+        let device = Device::default()
+            .suggested_area("kitchen")
+            .hw_version("rev B");
+        assert_json_eq!(
+            json!({
+              "sa": "kitchen",
+              "hw": "rev B"
+            }),
+            serde_json::to_value(&device).unwrap()
+        );
+    }
+
+    #[test]
+    fn accepts_https_configuration_url() {
+        let device = Device::default().configuration_url("https://device.local/admin");
+        assert!(device.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_ftp_configuration_url() {
+        let device = Device::default().configuration_url("ftp://device.local");
+        assert!(device.validate().is_err());
+    }
+
+    #[test]
+    fn can_route_sub_devices_via_hub() {
+        let hub = Device::default().name("hub").add_identifier("hub-01");
+        let mut sub_devices = vec![
+            Device::default().add_identifier("sensor-01"),
+            Device::default().add_identifier("sensor-02").via("hub-01"),
+        ];
+        Device::route_via(&hub, &mut sub_devices).unwrap();
+        for sub_device in &sub_devices {
+            assert_eq!(
+                json!("hub-01"),
+                serde_json::to_value(sub_device).unwrap()["via_device"]
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_sub_device_routed_via_another_hub() {
+        let hub = Device::default().add_identifier("hub-01");
+        let mut sub_devices = vec![
+            Device::default().add_identifier("sensor-01"),
+            Device::default().via_device("hub-02"),
+        ];
+        assert!(Device::route_via(&hub, &mut sub_devices).is_err());
+        assert_eq!(None, sub_devices[0].via_device);
+    }
+
+    #[test]
+    fn publish_options_use_entity_values() {
+        let entity = Entity::Number(Number::default().qos(Qos::ExactlyOnce).retain(true));
+        assert_eq!(
+            PublishOptions {
+                qos: Qos::ExactlyOnce,
+                retain: true,
+            },
+            PublishOptions::for_entity(&entity)
+        );
+    }
+
+    #[test]
+    fn publish_options_default_when_omitted() {
+        let number = Entity::Number(Number::default());
+        let camera = Entity::Camera(Camera::default());
+        assert_eq!(
+            PublishOptions::default(),
+            PublishOptions::for_entity(&number)
+        );
+        assert_eq!(
+            PublishOptions::default(),
+            PublishOptions::for_entity(&camera)
+        );
+    }
+
+    #[test]
+    fn default_availability_is_omitted() {
+        assert_eq!(AvailabilityMode::Latest, AvailabilityMode::default());
+        assert_json_eq!(
+            json!({}),
+            serde_json::to_value(Availability::default()).unwrap()
+        );
+        assert_json_eq!(
+            json!({ "avty": [{ "t": "~/availability" }] }),
+            serde_json::to_value(Availability::latest(vec![AvailabilityCheck::topic(
+                "~/availability"
+            )]))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn can_serialize_nullable() {
+        #[derive(Serialize, Deserialize, Default)]
+        #[serde(default)]
+        struct Config {
+            #[serde(skip_serializing_if = "Nullable::is_unset")]
+            attribute: Nullable<String>,
+        }
+
+        for (attribute, expected) in [
+            (Nullable::Unset, json!({})),
+            (Nullable::Null, json!({ "attribute": null })),
+            (
+                Nullable::Value("value".to_string()),
+                json!({ "attribute": "value" }),
+            ),
+        ] {
+            let config = Config { attribute };
+            assert_eq!(expected, serde_json::to_value(&config).unwrap());
+            let parsed: Config = serde_json::from_value(expected).unwrap();
+            assert_eq!(config.attribute, parsed.attribute);
+        }
+    }
+
+    #[test]
+    fn can_merge_availabilities() {
+        let device_wide = Availability::single_topic("device/availability").expire_after(300);
+        let entity = Availability::any(vec![
+            AvailabilityCheck::topic("device/availability"),
+            AvailabilityCheck::topic("entity/availability"),
+        ])
+        .expire_after(60);
+        assert_eq!(
+            Availability {
+                mode: AvailabilityMode::Latest,
+                availability: vec![
+                    AvailabilityCheck::topic("device/availability"),
+                    AvailabilityCheck::topic("entity/availability"),
+                ],
+                expire_after: Some(60),
+            },
+            device_wide.merge(entity)
+        );
+    }
+
+    #[test]
+    fn origin_from_crate_has_name_and_version() {
+        let origin = Origin::from_crate();
+        assert_eq!("ha-mqtt-discovery", origin.name);
+        assert_eq!(
+            Some(env!("CARGO_PKG_VERSION").to_string()),
+            origin.sw_version
+        );
+        assert_eq!(origin, crate::origin_from_crate!());
+    }
+
+    #[test]
+    fn publish_topics_reject_wildcards() {
+        assert_eq!(Ok(()), validate_publish_topic("home/cover/set"));
+        assert_eq!(
+            Err(TopicError::Wildcard),
+            validate_publish_topic("home/+/set")
+        );
+        assert_eq!(Err(TopicError::Wildcard), validate_publish_topic("home/#"));
+        assert_eq!(Ok(()), validate_topic("home/#"));
+    }
+
+    #[test]
+    fn topic_checks_wildcards_by_direction() {
+        assert_eq!(
+            "home/+/state",
+            Topic::subscribe("home/+/state").unwrap().as_str()
+        );
+        assert_eq!(Err(TopicError::Wildcard), Topic::publish("home/+/set"));
+        assert_eq!(Err(TopicError::Empty), Topic::subscribe(""));
+        assert_eq!(
+            "home/cover/set",
+            String::from(Topic::publish("home/cover/set").unwrap())
+        );
+    }
+
+    #[test]
+    fn can_serialize_device_model_id() {
+        let device = Device::default().model("Smart Plug").model_id("SP-100");
+        assert_json_eq!(
+            json!({
+              "mdl": "Smart Plug",
+              "mdl_id": "SP-100"
+            }),
+            serde_json::to_value(&device).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_serialize_device_serial_number() {
+        let device = Device::default().serial_number("SN-0042");
+        assert_json_eq!(
+            json!({ "sn": "SN-0042" }),
+            serde_json::to_value(&device).unwrap()
+        );
+    }
+
+    #[test]
+    fn unnamed_device_omits_name() {
+        let device = Device::default().add_identifier("device id");
+        assert_json_eq!(
+            json!({ "ids": ["device id"] }),
+            serde_json::to_value(&device).unwrap()
+        );
+    }
 }
@@ -1,7 +1,8 @@
 use super::common::Qos;
-use super::common::{Availability, Device, EntityCategory, Origin};
-use crate::Entity;
-use serde_derive::Serialize;
+use super::common::{Availability, Device, Encoding, EntityCategory, Nullable, Origin};
+use crate::{Entity, validate_topics};
+use anyhow::{Result, bail};
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Cover"
@@ -325,7 +326,8 @@ use serde_derive::Serialize;
 /// mosquitto_pub -h 127.0.0.1 -t home-assistant/cover/set -m "CLOSE"
 /// ```
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Cover {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -333,11 +335,11 @@ pub struct Cover {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -353,8 +355,8 @@ pub struct Cover {
     pub command_topic: Option<String>,
 
     /// Sets the [class of the device](/integrations/cover/#device_class), changing the device state and icon that is displayed on the frontend. The `device_class` can be `null`.
-    #[serde(rename = "dev_cla", skip_serializing_if = "Option::is_none")]
-    pub device_class: Option<String>,
+    #[serde(rename = "dev_cla", skip_serializing_if = "Nullable::is_unset")]
+    pub device_class: Nullable<String>,
 
     /// Flag which defines if the entity should be enabled when first added.
     #[serde(rename = "en", skip_serializing_if = "Option::is_none")]
@@ -362,7 +364,7 @@ pub struct Cover {
 
     /// The encoding of the payloads received and published messages. Set to `""` to disable decoding of incoming payload.
     #[serde(rename = "e", skip_serializing_if = "Option::is_none")]
-    pub encoding: Option<String>,
+    pub encoding: Option<Encoding>,
 
     /// Picture URL for the entity.
     #[serde(rename = "ent_pic", skip_serializing_if = "Option::is_none")]
@@ -393,16 +395,16 @@ pub struct Cover {
     pub optimistic: Option<bool>,
 
     /// The command payload that closes the cover.
-    #[serde(rename = "pl_cls", skip_serializing_if = "Option::is_none")]
-    pub payload_close: Option<String>,
+    #[serde(rename = "pl_cls", skip_serializing_if = "Nullable::is_unset")]
+    pub payload_close: Nullable<String>,
 
     /// The command payload that opens the cover.
-    #[serde(rename = "pl_open", skip_serializing_if = "Option::is_none")]
-    pub payload_open: Option<String>,
+    #[serde(rename = "pl_open", skip_serializing_if = "Nullable::is_unset")]
+    pub payload_open: Nullable<String>,
 
     /// The command payload that stops the cover.
-    #[serde(rename = "pl_stop", skip_serializing_if = "Option::is_none")]
-    pub payload_stop: Option<String>,
+    #[serde(rename = "pl_stop", skip_serializing_if = "Nullable::is_unset")]
+    pub payload_stop: Nullable<String>,
 
     /// Must be `cover`. Only allowed and required in [MQTT auto discovery device messages](/integrations/mqtt/#device-discovery-payload).
     #[serde(rename = "platform")]
@@ -510,11 +512,10 @@ pub struct Cover {
 }
 
 impl Cover {
-    /// Replaces `~` with this value in any MQTT topic attribute.
-    /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
-    pub fn topic_prefix<S: Into<String>>(mut self, topic_prefix: S) -> Self {
-        self.topic_prefix = Some(topic_prefix.into());
-        self
+    builder_field! {
+        /// Replaces `~` with this value in any MQTT topic attribute.
+        /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
+        topic_prefix: String
     }
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
@@ -529,10 +530,9 @@ impl Cover {
         self
     }
 
-    /// The category of the entity. (optional, default: None)
-    pub fn entity_category(mut self, entity_category: EntityCategory) -> Self {
-        self.entity_category = Some(entity_category);
-        self
+    builder_field! {
+        /// The category of the entity. (optional, default: None)
+        entity_category: EntityCategory
     }
 
     /// Defines how HA will check for entity availability.
@@ -541,90 +541,101 @@ impl Cover {
         self
     }
 
-    /// The MQTT topic to publish commands to control the cover.
-    pub fn command_topic<T: Into<String>>(mut self, command_topic: T) -> Self {
-        self.command_topic = Some(command_topic.into());
-        self
+    builder_field! {
+        /// The MQTT topic to publish commands to control the cover.
+        command_topic: String
     }
 
     /// Sets the [class of the device](/integrations/cover/#device_class), changing the device state and icon that is displayed on the frontend. The `device_class` can be `null`.
     pub fn device_class<T: Into<String>>(mut self, device_class: T) -> Self {
-        self.device_class = Some(device_class.into());
+        self.device_class = Nullable::Value(device_class.into());
         self
     }
 
-    /// Flag which defines if the entity should be enabled when first added.
-    pub fn enabled_by_default(mut self, enabled_by_default: bool) -> Self {
-        self.enabled_by_default = Some(enabled_by_default);
+    /// Explicitly sets the `device_class` to `null`, overriding the class Home Assistant would infer.
+    pub fn null_device_class(mut self) -> Self {
+        self.device_class = Nullable::Null;
         self
     }
 
-    /// The encoding of the payloads received and published messages. Set to `""` to disable decoding of incoming payload.
-    pub fn encoding<T: Into<String>>(mut self, encoding: T) -> Self {
-        self.encoding = Some(encoding.into());
-        self
+    builder_field! {
+        /// Flag which defines if the entity should be enabled when first added.
+        enabled_by_default: bool
     }
 
-    /// Picture URL for the entity.
-    pub fn entity_picture<T: Into<String>>(mut self, entity_picture: T) -> Self {
-        self.entity_picture = Some(entity_picture.into());
-        self
+    builder_field! {
+        /// The encoding of the payloads received and published messages. Set to `""` to disable decoding of incoming payload.
+        encoding: Into<Encoding>
     }
 
-    /// [Icon](/docs/configuration/customizing-devices/#icon) for the entity.
-    pub fn icon<T: Into<String>>(mut self, icon: T) -> Self {
-        self.icon = Some(icon.into());
-        self
+    builder_field! {
+        /// Picture URL for the entity.
+        entity_picture: String
     }
 
-    /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) to extract the JSON dictionary from messages received on the `json_attributes_topic`. Usage example can be found in [MQTT sensor](/integrations/sensor.mqtt/#json-attributes-template-configuration) documentation.
-    pub fn json_attributes_template<T: Into<String>>(
-        mut self,
-        json_attributes_template: T,
-    ) -> Self {
-        self.json_attributes_template = Some(json_attributes_template.into());
-        self
+    builder_field! {
+        /// [Icon](/docs/configuration/customizing-devices/#icon) for the entity.
+        icon: String
     }
 
-    /// The MQTT topic subscribed to receive a JSON dictionary payload and then set as sensor attributes. Usage example can be found in [MQTT sensor](/integrations/sensor.mqtt/#json-attributes-topic-configuration) documentation.
-    pub fn json_attributes_topic<T: Into<String>>(mut self, json_attributes_topic: T) -> Self {
-        self.json_attributes_topic = Some(json_attributes_topic.into());
-        self
+    builder_field! {
+        /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) to extract the JSON dictionary from messages received on the `json_attributes_topic`. Usage example can be found in [MQTT sensor](/integrations/sensor.mqtt/#json-attributes-template-configuration) documentation.
+        json_attributes_template: String
     }
 
-    /// The name of the cover. Can be set to `null` if only the device name is relevant.
-    pub fn name<T: Into<String>>(mut self, name: T) -> Self {
-        self.name = Some(name.into());
-        self
+    builder_field! {
+        /// The MQTT topic subscribed to receive a JSON dictionary payload and then set as sensor attributes. Usage example can be found in [MQTT sensor](/integrations/sensor.mqtt/#json-attributes-topic-configuration) documentation.
+        json_attributes_topic: String
     }
 
-    /// Used instead of `name` for automatic generation of `entity_id`
-    pub fn object_id<T: Into<String>>(mut self, object_id: T) -> Self {
-        self.object_id = Some(object_id.into());
-        self
+    builder_field! {
+        /// The name of the cover. Can be set to `null` if only the device name is relevant.
+        name: String
     }
 
-    /// Flag that defines if switch works in optimistic mode.
-    pub fn optimistic(mut self, optimistic: bool) -> Self {
-        self.optimistic = Some(optimistic);
-        self
+    builder_field! {
+        /// Used instead of `name` for automatic generation of `entity_id`
+        object_id: String
+    }
+
+    builder_field! {
+        /// Flag that defines if switch works in optimistic mode.
+        optimistic: bool
     }
 
     /// The command payload that closes the cover.
     pub fn payload_close<T: Into<String>>(mut self, payload_close: T) -> Self {
-        self.payload_close = Some(payload_close.into());
+        self.payload_close = Nullable::Value(payload_close.into());
+        self
+    }
+
+    /// Sets `payload_close` to `null`, disabling the close command.
+    pub fn null_payload_close(mut self) -> Self {
+        self.payload_close = Nullable::Null;
         self
     }
 
     /// The command payload that opens the cover.
     pub fn payload_open<T: Into<String>>(mut self, payload_open: T) -> Self {
-        self.payload_open = Some(payload_open.into());
+        self.payload_open = Nullable::Value(payload_open.into());
+        self
+    }
+
+    /// Sets `payload_open` to `null`, disabling the open command.
+    pub fn null_payload_open(mut self) -> Self {
+        self.payload_open = Nullable::Null;
         self
     }
 
     /// The command payload that stops the cover.
     pub fn payload_stop<T: Into<String>>(mut self, payload_stop: T) -> Self {
-        self.payload_stop = Some(payload_stop.into());
+        self.payload_stop = Nullable::Value(payload_stop.into());
+        self
+    }
+
+    /// Sets `payload_stop` to `null`, disabling the stop command.
+    pub fn null_payload_stop(mut self) -> Self {
+        self.payload_stop = Nullable::Null;
         self
     }
 
@@ -634,154 +645,129 @@ impl Cover {
         self
     }
 
-    /// Number which represents closed position.
-    pub fn position_closed(mut self, position_closed: i32) -> Self {
-        self.position_closed = Some(position_closed);
-        self
+    builder_field! {
+        /// Number which represents closed position.
+        position_closed: i32
     }
 
-    /// Number which represents open position.
-    pub fn position_open(mut self, position_open: i32) -> Self {
-        self.position_open = Some(position_open);
-        self
+    builder_field! {
+        /// Number which represents open position.
+        position_open: i32
     }
 
-    /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) that can be used to extract the payload for the `position_topic` topic. Within the template the following variables are available: `entity_id`, `position_open`; `position_closed`; `tilt_min`; `tilt_max`. The `entity_id` can be used to reference the entity's attributes with help of the [states](/docs/configuration/templating/#states) template function;
-    pub fn position_template<T: Into<String>>(mut self, position_template: T) -> Self {
-        self.position_template = Some(position_template.into());
-        self
+    builder_field! {
+        /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) that can be used to extract the payload for the `position_topic` topic. Within the template the following variables are available: `entity_id`, `position_open`; `position_closed`; `tilt_min`; `tilt_max`. The `entity_id` can be used to reference the entity's attributes with help of the [states](/docs/configuration/templating/#states) template function;
+        position_template: String
     }
 
-    /// The MQTT topic subscribed to receive cover position messages.
-    pub fn position_topic<T: Into<String>>(mut self, position_topic: T) -> Self {
-        self.position_topic = Some(position_topic.into());
-        self
+    builder_field! {
+        /// The MQTT topic subscribed to receive cover position messages.
+        position_topic: String
     }
 
-    /// The maximum QoS level to be used when receiving and publishing messages.
-    pub fn qos(mut self, qos: Qos) -> Self {
-        self.qos = Some(qos);
-        self
+    builder_field! {
+        /// The maximum QoS level to be used when receiving and publishing messages.
+        qos: Qos
     }
 
-    /// Defines if published messages should have the retain flag set.
-    pub fn retain(mut self, retain: bool) -> Self {
-        self.retain = Some(retain);
-        self
+    builder_field! {
+        /// Defines if published messages should have the retain flag set.
+        retain: bool
     }
 
-    /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) to define the position to be sent to the `set_position_topic` topic. Incoming position value is available for use in the template `{% raw %}{{ position }}{% endraw %}`. Within the template the following variables are available: `entity_id`, `position`, the target position in percent; `position_open`; `position_closed`; `tilt_min`; `tilt_max`. The `entity_id` can be used to reference the entity's attributes with help of the [states](/docs/configuration/templating/#states) template function;
-    pub fn set_position_template<T: Into<String>>(mut self, set_position_template: T) -> Self {
-        self.set_position_template = Some(set_position_template.into());
-        self
+    builder_field! {
+        /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) to define the position to be sent to the `set_position_topic` topic. Incoming position value is available for use in the template `{% raw %}{{ position }}{% endraw %}`. Within the template the following variables are available: `entity_id`, `position`, the target position in percent; `position_open`; `position_closed`; `tilt_min`; `tilt_max`. The `entity_id` can be used to reference the entity's attributes with help of the [states](/docs/configuration/templating/#states) template function;
+        set_position_template: String
     }
 
-    /// The MQTT topic to publish position commands to. You need to set position_topic as well if you want to use position topic. Use template if position topic wants different values than within range `position_closed` - `position_open`. If template is not defined and `position_closed != 100` and `position_open != 0` then proper position value is calculated from percentage position.
-    pub fn set_position_topic<T: Into<String>>(mut self, set_position_topic: T) -> Self {
-        self.set_position_topic = Some(set_position_topic.into());
-        self
+    builder_field! {
+        /// The MQTT topic to publish position commands to. You need to set position_topic as well if you want to use position topic. Use template if position topic wants different values than within range `position_closed` - `position_open`. If template is not defined and `position_closed != 100` and `position_open != 0` then proper position value is calculated from percentage position.
+        set_position_topic: String
     }
 
-    /// The payload that represents the closed state.
-    pub fn state_closed<T: Into<String>>(mut self, state_closed: T) -> Self {
-        self.state_closed = Some(state_closed.into());
-        self
+    builder_field! {
+        /// The payload that represents the closed state.
+        state_closed: String
     }
 
-    /// The payload that represents the closing state.
-    pub fn state_closing<T: Into<String>>(mut self, state_closing: T) -> Self {
-        self.state_closing = Some(state_closing.into());
-        self
+    builder_field! {
+        /// The payload that represents the closing state.
+        state_closing: String
     }
 
-    /// The payload that represents the open state.
-    pub fn state_open<T: Into<String>>(mut self, state_open: T) -> Self {
-        self.state_open = Some(state_open.into());
-        self
+    builder_field! {
+        /// The payload that represents the open state.
+        state_open: String
     }
 
-    /// The payload that represents the opening state.
-    pub fn state_opening<T: Into<String>>(mut self, state_opening: T) -> Self {
-        self.state_opening = Some(state_opening.into());
-        self
+    builder_field! {
+        /// The payload that represents the opening state.
+        state_opening: String
     }
 
-    /// The payload that represents the stopped state (for covers that do not report `open`/`closed` state).
-    pub fn state_stopped<T: Into<String>>(mut self, state_stopped: T) -> Self {
-        self.state_stopped = Some(state_stopped.into());
-        self
+    builder_field! {
+        /// The payload that represents the stopped state (for covers that do not report `open`/`closed` state).
+        state_stopped: String
     }
 
-    /// The MQTT topic subscribed to receive cover state messages. State topic can only read a (`open`, `opening`, `closed`, `closing` or `stopped`) state.  A "None" payload resets to an `unknown` state. An empty payload is ignored.
-    pub fn state_topic<T: Into<String>>(mut self, state_topic: T) -> Self {
-        self.state_topic = Some(state_topic.into());
-        self
+    builder_field! {
+        /// The MQTT topic subscribed to receive cover state messages. State topic can only read a (`open`, `opening`, `closed`, `closing` or `stopped`) state.  A "None" payload resets to an `unknown` state. An empty payload is ignored.
+        state_topic: String
     }
 
-    /// The value that will be sent on a `close_cover_tilt` command.
-    pub fn tilt_closed_value(mut self, tilt_closed_value: i32) -> Self {
-        self.tilt_closed_value = Some(tilt_closed_value);
-        self
+    builder_field! {
+        /// The value that will be sent on a `close_cover_tilt` command.
+        tilt_closed_value: i32
     }
 
-    /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) that can be used to extract the payload for the `tilt_command_topic` topic. Within the template the following variables are available: `entity_id`, `tilt_position`, the target tilt position in percent; `position_open`; `position_closed`; `tilt_min`; `tilt_max`. The `entity_id` can be used to reference the entity's attributes with help of the [states](/docs/configuration/templating/#states) template function;
-    pub fn tilt_command_template<T: Into<String>>(mut self, tilt_command_template: T) -> Self {
-        self.tilt_command_template = Some(tilt_command_template.into());
-        self
+    builder_field! {
+        /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) that can be used to extract the payload for the `tilt_command_topic` topic. Within the template the following variables are available: `entity_id`, `tilt_position`, the target tilt position in percent; `position_open`; `position_closed`; `tilt_min`; `tilt_max`. The `entity_id` can be used to reference the entity's attributes with help of the [states](/docs/configuration/templating/#states) template function;
+        tilt_command_template: String
     }
 
-    /// The MQTT topic to publish commands to control the cover tilt.
-    pub fn tilt_command_topic<T: Into<String>>(mut self, tilt_command_topic: T) -> Self {
-        self.tilt_command_topic = Some(tilt_command_topic.into());
-        self
+    builder_field! {
+        /// The MQTT topic to publish commands to control the cover tilt.
+        tilt_command_topic: String
     }
 
-    /// The maximum tilt value.
-    pub fn tilt_max(mut self, tilt_max: i32) -> Self {
-        self.tilt_max = Some(tilt_max);
-        self
+    builder_field! {
+        /// The maximum tilt value.
+        tilt_max: i32
     }
 
-    /// The minimum tilt value.
-    pub fn tilt_min(mut self, tilt_min: i32) -> Self {
-        self.tilt_min = Some(tilt_min);
-        self
+    builder_field! {
+        /// The minimum tilt value.
+        tilt_min: i32
     }
 
-    /// The value that will be sent on an `open_cover_tilt` command.
-    pub fn tilt_opened_value(mut self, tilt_opened_value: i32) -> Self {
-        self.tilt_opened_value = Some(tilt_opened_value);
-        self
+    builder_field! {
+        /// The value that will be sent on an `open_cover_tilt` command.
+        tilt_opened_value: i32
     }
 
-    /// Flag that determines if tilt works in optimistic mode.
-    pub fn tilt_optimistic(mut self, tilt_optimistic: bool) -> Self {
-        self.tilt_optimistic = Some(tilt_optimistic);
-        self
+    builder_field! {
+        /// Flag that determines if tilt works in optimistic mode.
+        tilt_optimistic: bool
     }
 
-    /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) that can be used to extract the payload for the `tilt_status_topic` topic. Within the template the following variables are available: `entity_id`, `position_open`; `position_closed`; `tilt_min`; `tilt_max`. The `entity_id` can be used to reference the entity's attributes with help of the [states](/docs/configuration/templating/#states) template function;
-    pub fn tilt_status_template<T: Into<String>>(mut self, tilt_status_template: T) -> Self {
-        self.tilt_status_template = Some(tilt_status_template.into());
-        self
+    builder_field! {
+        /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) that can be used to extract the payload for the `tilt_status_topic` topic. Within the template the following variables are available: `entity_id`, `position_open`; `position_closed`; `tilt_min`; `tilt_max`. The `entity_id` can be used to reference the entity's attributes with help of the [states](/docs/configuration/templating/#states) template function;
+        tilt_status_template: String
     }
 
-    /// The MQTT topic subscribed to receive tilt status update values.
-    pub fn tilt_status_topic<T: Into<String>>(mut self, tilt_status_topic: T) -> Self {
-        self.tilt_status_topic = Some(tilt_status_topic.into());
-        self
+    builder_field! {
+        /// The MQTT topic subscribed to receive tilt status update values.
+        tilt_status_topic: String
     }
 
-    /// An ID that uniquely identifies this cover. If two covers have the same unique ID, Home Assistant will raise an exception. Required when used with device-based discovery.
-    pub fn unique_id<T: Into<String>>(mut self, unique_id: T) -> Self {
-        self.unique_id = Some(unique_id.into());
-        self
+    builder_field! {
+        /// An ID that uniquely identifies this cover. If two covers have the same unique ID, Home Assistant will raise an exception. Required when used with device-based discovery.
+        unique_id: String
     }
 
-    /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) that can be used to extract the payload for the `state_topic` topic.
-    pub fn value_template<T: Into<String>>(mut self, value_template: T) -> Self {
-        self.value_template = Some(value_template.into());
-        self
+    builder_field! {
+        /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) that can be used to extract the payload for the `state_topic` topic.
+        value_template: String
     }
 }
 
@@ -842,3 +828,654 @@ impl From<Cover> for Entity {
         Entity::Cover(value)
     }
 }
+
+/// A state a cover entity can be in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoverState {
+    Open,
+    Opening,
+    Closed,
+    Closing,
+    Stopped,
+}
+
+/// A command Home Assistant can send to a cover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoverCommand {
+    Open,
+    Close,
+    Stop,
+}
+
+/// The state of a cover combined from its state and position payloads by [Cover::resolve].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CoverSnapshot {
+    pub state: Option<CoverState>,
+    /// The position scaled to the 0 (closed) to 100 (open) range.
+    pub position: Option<i32>,
+}
+
+/// The commands a cover offers, as reported by [Cover::available_commands].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CoverCommands {
+    pub open: bool,
+    pub close: bool,
+    pub stop: bool,
+}
+
+/// The range accepted by [Cover::validate] for positions and tilt values.
+pub const POSITION_RANGE: std::ops::RangeInclusive<i32> = -100_000..=100_000;
+
+impl Cover {
+    /// A cover without origin and device information, same as [Cover::default].
+    /// Empty `o` and `dev` objects are omitted when serializing, so the payload only carries the attributes set afterwards.
+    pub fn minimal() -> Self {
+        Self::default()
+    }
+
+    /// A garage door cover with the `garage` device class, controlled by `OPEN`, `CLOSE` and `STOP` commands
+    /// on `command_topic` and reporting `open`, `opening`, `closed`, `closing` or `stopped` on `state_topic`.
+    pub fn garage_door<C: Into<String>, S: Into<String>>(command_topic: C, state_topic: S) -> Self {
+        Self::default()
+            .device_class("garage")
+            .command_topic(command_topic)
+            .state_topic(state_topic)
+            .payload_open("OPEN")
+            .payload_close("CLOSE")
+            .payload_stop("STOP")
+            .state_open("open")
+            .state_opening("opening")
+            .state_closed("closed")
+            .state_closing("closing")
+            .state_stopped("stopped")
+    }
+
+    /// Simulates the state transition Home Assistant applies to a cover working in optimistic mode.
+    ///
+    /// `open` and `close` commands immediately move the cover to `open` and `closed` respectively.
+    /// A `stop` command is only published, so the cover keeps its state.
+    pub fn simulate(&self, current: CoverState, command: CoverCommand) -> CoverState {
+        match command {
+            CoverCommand::Open => CoverState::Open,
+            CoverCommand::Close => CoverState::Closed,
+            CoverCommand::Stop => current,
+        }
+    }
+}
+
+impl Cover {
+    /// Sets `topic_prefix` to `base` and rewrites the topics starting with `base`, including the availability
+    /// topics, to be relative to it, e.g. `kitchen/blind/set` becomes `~/set` with the `kitchen/blind` base.
+    pub fn with_base_topic(mut self, base: &str) -> Self {
+        let base = base.strip_suffix('/').unwrap_or(base);
+        let rebase = |topic: &mut String| {
+            if let Some(rest) = topic.strip_prefix(base)
+                && (rest.is_empty() || rest.starts_with('/'))
+            {
+                *topic = format!("~{rest}");
+            }
+        };
+        for topic in [
+            &mut self.command_topic,
+            &mut self.json_attributes_topic,
+            &mut self.position_topic,
+            &mut self.set_position_topic,
+            &mut self.state_topic,
+            &mut self.tilt_command_topic,
+            &mut self.tilt_status_topic,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            rebase(topic);
+        }
+        for check in &mut self.availability.availability {
+            rebase(&mut check.topic);
+        }
+        self.topic_prefix = Some(base.to_string());
+        self
+    }
+
+    /// Generates a `position_template` for a position topic carrying both the position and the tilt position
+    /// as a JSON object, e.g. `{"pos": 40, "tilt": 3}` with `position_key` `pos` and `tilt_key` `tilt`.
+    pub fn combined_position_tilt_template(position_key: &str, tilt_key: &str) -> String {
+        format!(
+            r#"{{"position": {{{{ value_json["{position_key}"] }}}}, "tilt_position": {{{{ value_json["{tilt_key}"] }}}}}}"#
+        )
+    }
+
+    /// Combines payloads received on `state_topic` and `position_topic` the way Home Assistant does:
+    /// the state payload drives the state and the position payload drives the position, scaled from the
+    /// (`position_closed`...`position_open`) range. Without a recognized state payload, the state follows the position,
+    /// `closed` at 0 and `open` otherwise. A `stopped` state settles to `closed` or `open` when the position is known.
+    pub fn resolve(
+        &self,
+        state_payload: Option<&str>,
+        position_payload: Option<&str>,
+    ) -> CoverSnapshot {
+        let position = position_payload.and_then(|payload| {
+            let position: i32 = payload.trim().parse().ok()?;
+            let closed = self.position_closed.unwrap_or(0);
+            let open = self.position_open.unwrap_or(100);
+            if open == closed {
+                return None;
+            }
+            let scaled = (i64::from(position) - i64::from(closed)) * 100
+                / (i64::from(open) - i64::from(closed));
+            Some(scaled.clamp(0, 100) as i32)
+        });
+        let state = state_payload.and_then(|payload| {
+            [
+                (&self.state_open, "open", CoverState::Open),
+                (&self.state_opening, "opening", CoverState::Opening),
+                (&self.state_closed, "closed", CoverState::Closed),
+                (&self.state_closing, "closing", CoverState::Closing),
+                (&self.state_stopped, "stopped", CoverState::Stopped),
+            ]
+            .into_iter()
+            .find(|(configured, default, _)| payload == configured.as_deref().unwrap_or(default))
+            .map(|(_, _, state)| state)
+        });
+        let from_position = |position| match position {
+            0 => CoverState::Closed,
+            _ => CoverState::Open,
+        };
+        let state = match (state, position) {
+            (Some(CoverState::Stopped) | None, Some(position)) => Some(from_position(position)),
+            (state, _) => state,
+        };
+        CoverSnapshot { state, position }
+    }
+
+    /// Suggests a state topic following the `<base>/set` → `<base>/state` convention many devices use,
+    /// e.g. `home/cover/state` for the `home/cover/set` command topic. Other command topics yield `None`.
+    pub fn derive_state_topic(&self) -> Option<String> {
+        let base = self.command_topic.as_deref()?.strip_suffix("/set")?;
+        Some(format!("{base}/state"))
+    }
+
+    /// Whether Home Assistant updates the tilt position optimistically: `tilt_optimistic` when set,
+    /// otherwise only when there is no `tilt_status_topic` to report it.
+    pub fn effective_tilt_optimistic(&self) -> bool {
+        self.tilt_optimistic
+            .unwrap_or(self.tilt_status_topic.is_none())
+    }
+
+    /// The commands Home Assistant offers for the cover. A cover without `command_topic` offers none,
+    /// otherwise a command is disabled by setting its payload to `null`.
+    pub fn available_commands(&self) -> CoverCommands {
+        let enabled =
+            |payload: &Nullable<String>| self.command_topic.is_some() && *payload != Nullable::Null;
+        CoverCommands {
+            open: enabled(&self.payload_open),
+            close: enabled(&self.payload_close),
+            stop: enabled(&self.payload_stop),
+        }
+    }
+
+    /// Computes the payload Home Assistant publishes on `set_position_topic` to move the cover to `pct` percent
+    /// open when no `set_position_template` is set: the percentage is mapped linearly onto the range from
+    /// `position_closed` (default 0) to `position_open` (default 100) and rounded to the nearest integer,
+    /// with ties rounded to even like Python's `round` used by Home Assistant.
+    /// Percentages outside `[0, 100]` are clamped.
+    pub fn compute_set_position_payload(&self, pct: i32) -> String {
+        let closed = self.position_closed.unwrap_or(0) as f64;
+        let open = self.position_open.unwrap_or(100) as f64;
+        let pct = pct.clamp(0, 100) as f64;
+        let position = closed + (open - closed) * pct / 100.0;
+        format!("{}", position.round_ties_even() as i64)
+    }
+
+    /// Checks the tilt configuration when `tilt_command_topic` is set: `tilt_min` must be less than `tilt_max`,
+    /// and `tilt_closed_value` and `tilt_opened_value` must lie within `[tilt_min, tilt_max]`.
+    /// Unset values take Home Assistant's defaults: 0 for `tilt_min` and `tilt_closed_value`, 100 for `tilt_max`
+    /// and `tilt_opened_value`.
+    ///
+    /// Positions and tilt values are also checked to lie within [POSITION_RANGE], which catches overflowing
+    /// values, e.g. produced by a template, while leaving room for any real device.
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        for (name, value) in [
+            ("position_closed", self.position_closed),
+            ("position_open", self.position_open),
+            ("tilt_closed_value", self.tilt_closed_value),
+            ("tilt_min", self.tilt_min),
+            ("tilt_max", self.tilt_max),
+            ("tilt_opened_value", self.tilt_opened_value),
+        ] {
+            if let Some(value) = value
+                && !POSITION_RANGE.contains(&value)
+            {
+                bail!("{name} ({value}) is outside of {POSITION_RANGE:?}");
+            }
+        }
+        if self.tilt_command_topic.is_some() {
+            let tilt_min = self.tilt_min.unwrap_or(0);
+            let tilt_max = self.tilt_max.unwrap_or(100);
+            if tilt_min >= tilt_max {
+                bail!("tilt_min ({tilt_min}) must be less than tilt_max ({tilt_max})");
+            }
+            for (name, value) in [
+                ("tilt_closed_value", self.tilt_closed_value.unwrap_or(0)),
+                ("tilt_opened_value", self.tilt_opened_value.unwrap_or(100)),
+            ] {
+                if !(tilt_min..=tilt_max).contains(&value) {
+                    bail!("{name} ({value}) must be within [{tilt_min}, {tilt_max}]");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports configuration that is valid but likely a mistake, e.g. `optimistic` forced together with
+    /// `state_topic`, so Home Assistant assumes the outcome of commands instead of waiting for the device.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.optimistic == Some(true) && self.state_topic.is_some() {
+            warnings.push(
+                "optimistic is forced while state_topic is set, so commands update the state before the device confirms"
+                    .to_string(),
+            );
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_STATES: [CoverState; 5] = [
+        CoverState::Open,
+        CoverState::Opening,
+        CoverState::Closed,
+        CoverState::Closing,
+        CoverState::Stopped,
+    ];
+
+    #[test]
+    fn open_command_opens_from_any_state() {
+        let cover = Cover::default();
+        for state in ALL_STATES {
+            assert_eq!(
+                CoverState::Open,
+                cover.simulate(state, CoverCommand::Open),
+                "from {state:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn close_command_closes_from_any_state() {
+        let cover = Cover::default();
+        for state in ALL_STATES {
+            assert_eq!(
+                CoverState::Closed,
+                cover.simulate(state, CoverCommand::Close),
+                "from {state:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn stop_command_keeps_state() {
+        let cover = Cover::default();
+        for state in [
+            CoverState::Open,
+            CoverState::Opening,
+            CoverState::Closed,
+            CoverState::Closing,
+            CoverState::Stopped,
+        ] {
+            assert_eq!(
+                state,
+                cover.simulate(state, CoverCommand::Stop),
+                "from {state:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn can_generate_combined_position_tilt_template() {
+        let template = Cover::combined_position_tilt_template("pos", "tilt");
+        assert_eq!(
+            r#"{"position": {{ value_json["pos"] }}, "tilt_position": {{ value_json["tilt"] }}}"#,
+            template
+        );
+        assert_eq!(
+            template.matches("{{").count(),
+            template.matches("}}").count()
+        );
+    }
+
+    #[test]
+    fn can_serialize_null_device_class() {
+        let json = serde_json::to_value(Cover::default().null_device_class()).unwrap();
+        assert_eq!(Some(&serde_json::Value::Null), json.get("dev_cla"));
+        let parsed: Cover = serde_json::from_value(json).unwrap();
+        assert_eq!(Nullable::Null, parsed.device_class);
+        let json = serde_json::to_value(Cover::default()).unwrap();
+        assert_eq!(None, json.get("dev_cla"));
+    }
+
+    #[test]
+    fn can_rebase_topics() {
+        let cover = Cover::default()
+            .command_topic("kitchen/blind/set")
+            .state_topic("kitchen/blind")
+            .position_topic("kitchen/blinds/position")
+            .tilt_status_topic("~/tilt")
+            .availability(Availability::single_topic("kitchen/blind/availability"))
+            .with_base_topic("kitchen/blind/");
+        assert_eq!(Some("kitchen/blind".to_string()), cover.topic_prefix);
+        assert_eq!(Some("~/set".to_string()), cover.command_topic);
+        assert_eq!(Some("~".to_string()), cover.state_topic);
+        assert_eq!(
+            Some("kitchen/blinds/position".to_string()),
+            cover.position_topic
+        );
+        assert_eq!(Some("~/tilt".to_string()), cover.tilt_status_topic);
+        assert_eq!("~/availability", cover.availability.availability[0].topic);
+    }
+
+    #[test]
+    fn generated_setters_serialize_unchanged() {
+        let cover = Cover::default()
+            .topic_prefix("kitchen/blind")
+            .entity_category(EntityCategory::Config)
+            .command_topic("~/set")
+            .enabled_by_default(true)
+            .name("Blind")
+            .optimistic(false)
+            .position_closed(0)
+            .position_open(100)
+            .qos(Qos::AtLeastOnce)
+            .tilt_min(0)
+            .tilt_max(6)
+            .unique_id("kitchen_blind");
+        assert_eq!(
+            serde_json::json!({
+                "~": "kitchen/blind",
+                "ent_cat": "config",
+                "cmd_t": "~/set",
+                "en": true,
+                "name": "Blind",
+                "opt": false,
+                "platform": "cover",
+                "pos_clsd": 0,
+                "pos_open": 100,
+                "qos": "1",
+                "tilt_min": 0,
+                "tilt_max": 6,
+                "uniq_id": "kitchen_blind"
+            }),
+            serde_json::to_value(&cover).unwrap()
+        );
+    }
+
+    #[test]
+    fn accepts_tilt_values_within_range() {
+        let cover = Cover::default()
+            .tilt_command_topic("~/tilt")
+            .tilt_min(0)
+            .tilt_max(180)
+            .tilt_closed_value(70)
+            .tilt_opened_value(180);
+        assert!(cover.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_tilt_min_not_below_tilt_max() {
+        let cover = Cover::default()
+            .tilt_command_topic("~/tilt")
+            .tilt_min(6)
+            .tilt_max(6);
+        assert!(cover.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_range_tilt_values() {
+        let closed = Cover::default()
+            .tilt_command_topic("~/tilt")
+            .tilt_min(10)
+            .tilt_closed_value(0);
+        assert!(closed.validate().is_err());
+
+        // The default tilt_opened_value of 100 is out of [0, 6].
+        let opened = Cover::default().tilt_command_topic("~/tilt").tilt_max(6);
+        assert!(opened.validate().is_err());
+    }
+
+    #[test]
+    fn ignores_tilt_values_without_tilt_command_topic() {
+        assert!(Cover::default().tilt_min(6).tilt_max(0).validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_json_attributes_template_without_topic() {
+        let cover = Cover::default().json_attributes_template("{{ value_json }}");
+        assert!(Entity::from(cover.clone()).validate().is_err());
+        let cover = cover.json_attributes_topic("~/attributes");
+        assert!(Entity::from(cover).validate().is_ok());
+    }
+
+    #[test]
+    fn empty_origin_and_device_are_omitted() {
+        assert_eq!(Cover::default(), Cover::minimal());
+        let json = serde_json::to_value(Cover::minimal().command_topic("~/set")).unwrap();
+        assert!(json.get("o").is_none());
+        assert!(json.get("dev").is_none());
+
+        let cover = Cover::default()
+            .origin(Origin::new("app"))
+            .device(Device::default().add_identifier("blind"));
+        let json = serde_json::to_value(cover).unwrap();
+        assert_eq!(serde_json::json!({ "name": "app" }), json["o"]);
+        assert_eq!(serde_json::json!({ "ids": ["blind"] }), json["dev"]);
+    }
+
+    #[test]
+    fn garage_door_preset() {
+        let cover = Cover::garage_door("garage/door/set", "garage/door/state");
+        let json = serde_json::to_value(&cover).unwrap();
+        assert_eq!("garage", json["dev_cla"]);
+        assert_eq!("garage/door/set", json["cmd_t"]);
+        assert_eq!("garage/door/state", json["stat_t"]);
+        assert_eq!("OPEN", json["pl_open"]);
+        assert_eq!("stopped", json["stat_stopped"]);
+    }
+
+    #[test]
+    fn raw_encoding_serializes_as_empty_string() {
+        let json = serde_json::to_value(Cover::default().encoding(Encoding::Raw)).unwrap();
+        assert_eq!(serde_json::json!(""), json["e"]);
+    }
+
+    #[test]
+    fn set_position_payload_with_default_range() {
+        let cover = Cover::default();
+        assert_eq!("0", cover.compute_set_position_payload(0));
+        assert_eq!("42", cover.compute_set_position_payload(42));
+        assert_eq!("100", cover.compute_set_position_payload(150));
+    }
+
+    #[test]
+    fn set_position_payload_with_custom_range() {
+        let cover = Cover::default().position_closed(0).position_open(255);
+        assert_eq!("128", cover.compute_set_position_payload(50));
+        // 76.5 rounds to the even neighbour rather than away from zero.
+        assert_eq!("76", cover.compute_set_position_payload(30));
+        assert_eq!("255", cover.compute_set_position_payload(100));
+
+        let inverted = Cover::default().position_closed(100).position_open(0);
+        assert_eq!("75", inverted.compute_set_position_payload(25));
+    }
+
+    #[test]
+    fn warns_about_forced_optimistic_mode() {
+        let cover = Cover::default()
+            .command_topic("~/set")
+            .state_topic("~/state")
+            .optimistic(true);
+        assert_eq!(1, cover.warnings().len());
+        assert!(cover.validate().is_ok());
+        assert!(cover.optimistic(false).warnings().is_empty());
+    }
+
+    #[test]
+    fn rejects_out_of_range_positions() {
+        assert!(
+            Cover::default()
+                .position_open(1_000_000)
+                .validate()
+                .is_err()
+        );
+        assert!(Cover::default().tilt_min(-100_001).validate().is_err());
+        assert!(Cover::default().position_open(65_535).validate().is_ok());
+    }
+
+    #[test]
+    fn disabled_close_command_is_not_available() {
+        let cover = Cover::default()
+            .command_topic("home/cover/set")
+            .payload_open("on")
+            .null_payload_close()
+            .payload_stop("on");
+        assert_eq!(
+            CoverCommands {
+                open: true,
+                close: false,
+                stop: true
+            },
+            cover.available_commands()
+        );
+    }
+
+    #[test]
+    fn null_open_and_stop_payloads_disable_commands() {
+        let cover = Cover::default()
+            .command_topic("home/cover/set")
+            .null_payload_open()
+            .null_payload_stop();
+        assert_eq!(
+            CoverCommands {
+                open: false,
+                close: true,
+                stop: false
+            },
+            cover.available_commands()
+        );
+    }
+
+    #[test]
+    fn cover_without_command_topic_has_no_commands() {
+        assert_eq!(
+            CoverCommands::default(),
+            Cover::default().available_commands()
+        );
+    }
+
+    #[test]
+    fn derives_state_topic_from_set_command_topic() {
+        let cover = Cover::default().command_topic("home/cover/set");
+        assert_eq!(
+            Some("home/cover/state".to_string()),
+            cover.derive_state_topic()
+        );
+        let cover = Cover::default().command_topic("home/cover/command");
+        assert_eq!(None, cover.derive_state_topic());
+    }
+
+    #[test]
+    fn can_serialize_payload_close_states() {
+        let payload_close =
+            |cover: Cover| serde_json::to_value(cover).unwrap().get("pl_cls").cloned();
+        assert_eq!(None, payload_close(Cover::default()));
+        assert_eq!(
+            Some(serde_json::Value::Null),
+            payload_close(Cover::default().null_payload_close())
+        );
+        assert_eq!(
+            Some(serde_json::json!("CLOSE")),
+            payload_close(Cover::default().payload_close("CLOSE"))
+        );
+    }
+
+    #[test]
+    fn null_close_payload_disables_close_command() {
+        let cover = Cover::default()
+            .command_topic("home/cover/set")
+            .null_payload_close();
+        assert!(!cover.available_commands().close);
+        assert!(cover.available_commands().open);
+    }
+
+    #[test]
+    fn rejects_wildcard_in_command_topic() {
+        let cover = Cover::default().command_topic("home/+/set");
+        assert!(cover.validate().is_err());
+        assert!(Entity::from(cover).validate().is_err());
+        let cover = Cover::default()
+            .command_topic("home/cover/set")
+            .state_topic("home/+/state");
+        assert!(cover.validate().is_ok());
+    }
+
+    #[test]
+    fn tilt_is_optimistic_without_status_topic() {
+        assert!(Cover::default().effective_tilt_optimistic());
+        assert!(
+            !Cover::default()
+                .tilt_status_topic("home/cover/tilt")
+                .effective_tilt_optimistic()
+        );
+        assert!(
+            Cover::default()
+                .tilt_status_topic("home/cover/tilt")
+                .tilt_optimistic(true)
+                .effective_tilt_optimistic()
+        );
+    }
+
+    #[test]
+    fn state_and_position_resolve_together() {
+        let cover = Cover::default()
+            .state_topic("home/cover/state")
+            .position_topic("home/cover/position");
+        assert_eq!(
+            CoverSnapshot {
+                state: Some(CoverState::Opening),
+                position: Some(40)
+            },
+            cover.resolve(Some("opening"), Some("40"))
+        );
+        assert_eq!(
+            CoverSnapshot {
+                state: Some(CoverState::Open),
+                position: Some(40)
+            },
+            cover.resolve(Some("stopped"), Some("40"))
+        );
+        assert_eq!(
+            CoverSnapshot {
+                state: Some(CoverState::Closed),
+                position: Some(0)
+            },
+            cover.resolve(None, Some("0"))
+        );
+        assert_eq!(
+            CoverSnapshot {
+                state: Some(CoverState::Stopped),
+                position: None
+            },
+            cover.resolve(Some("stopped"), None)
+        );
+    }
+
+    #[test]
+    fn position_is_scaled_to_percent() {
+        let cover = Cover::default().position_closed(0).position_open(255);
+        assert_eq!(Some(50), cover.resolve(None, Some("128")).position);
+        assert_eq!(Some(100), cover.resolve(None, Some("300")).position);
+    }
+}
@@ -0,0 +1,202 @@
+use super::common::{Device, Origin};
+use crate::Entity;
+use serde::ser::{Error, SerializeMap};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Several entities of a single device, published at once using
+/// [device-based discovery](https://www.home-assistant.io/integrations/mqtt/#device-discovery-payload).
+///
+/// The device and origin are shared by all the components, so they are serialized once at the top level
+/// and stripped from each component. Every component keeps its `platform` attribute.
+#[derive(Clone)]
+pub struct DeviceBundle {
+    /// The device all the components belong to.
+    pub device: Device,
+    /// The origin of all the components.
+    pub origin: Origin,
+    /// The components of the device, keyed by their object ID.
+    pub components: BTreeMap<String, Entity>,
+}
+
+impl DeviceBundle {
+    /// A bundle of the given device and origin, without components yet.
+    pub fn new(device: Device, origin: Origin) -> Self {
+        Self {
+            device,
+            origin,
+            components: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a component to the device. A component with the same object ID is replaced.
+    pub fn add_component<S: Into<String>, E: Into<Entity>>(
+        mut self,
+        object_id: S,
+        component: E,
+    ) -> Self {
+        self.components.insert(object_id.into(), component.into());
+        self
+    }
+
+    /// Makes the device of every bundle in `bundles` route its messages via `hub`, as [`Device::route_via`] does
+    /// for single devices. Fails without changing any bundle if a device already references a different hub.
+    pub fn route_via(hub: &Device, bundles: &mut [DeviceBundle]) -> anyhow::Result<()> {
+        let hub_identifier =
+            Device::hub_identifier(hub, bundles.iter().map(|bundle| &bundle.device))?;
+        for bundle in bundles.iter_mut() {
+            bundle.device.via_device = Some(hub_identifier.clone());
+        }
+        Ok(())
+    }
+
+    /// Splits the bundle into standalone entities, each one carrying the shared device and origin.
+    pub fn into_entities(self) -> Vec<Entity> {
+        self.components
+            .into_values()
+            .map(|mut entity| {
+                entity.set_device(self.device.clone());
+                entity.set_origin(self.origin.clone());
+                entity
+            })
+            .collect()
+    }
+}
+
+impl serde::ser::Serialize for DeviceBundle {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut components = serde_json::Map::new();
+        for (object_id, component) in &self.components {
+            let mut attributes = component.get_attributes().map_err(S::Error::custom)?;
+            if let Value::Object(attributes) = &mut attributes {
+                attributes.remove("dev");
+                attributes.remove("o");
+            }
+            components.insert(object_id.clone(), attributes);
+        }
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("dev", &self.device)?;
+        map.serialize_entry("o", &self.origin)?;
+        map.serialize_entry("cmps", &components)?;
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt::cover::Cover;
+    use crate::mqtt::sensor::Sensor;
+
+    #[test]
+    fn can_serialize_device_bundle() {
+        let bundle = DeviceBundle::new(
+            Device::default().add_identifier("blind-01"),
+            Origin::new("origin"),
+        )
+        .add_component(
+            "blind-01_cover",
+            Cover::default()
+                .device(Device::default().add_identifier("blind-01"))
+                .origin(Origin::new("origin"))
+                .unique_id("blind-01_cover")
+                .command_topic("blind-01/set"),
+        )
+        .add_component(
+            "blind-01_battery",
+            Sensor::default()
+                .unique_id("blind-01_battery")
+                .state_topic("blind-01/battery"),
+        );
+
+        let json = serde_json::to_value(&bundle).unwrap();
+        assert_eq!(serde_json::json!({ "ids": ["blind-01"] }), json["dev"]);
+        assert_eq!(serde_json::json!({ "name": "origin" }), json["o"]);
+
+        let cover = &json["cmps"]["blind-01_cover"];
+        assert_eq!("cover", cover["platform"]);
+        assert_eq!("blind-01/set", cover["cmd_t"]);
+        assert!(cover.get("dev").is_none());
+        assert!(cover.get("o").is_none());
+
+        let sensor = &json["cmps"]["blind-01_battery"];
+        assert_eq!("sensor", sensor["platform"]);
+        assert_eq!("blind-01/battery", sensor["stat_t"]);
+    }
+
+    #[test]
+    fn can_route_bundles_via_hub() {
+        let hub = Device::default().name("hub").add_identifier("hub-01");
+        let bundle = |identifier: &str| {
+            DeviceBundle::new(
+                Device::default().add_identifier(identifier),
+                Origin::new("origin"),
+            )
+            .add_component(
+                format!("{identifier}_battery"),
+                Sensor::default()
+                    .unique_id(format!("{identifier}_battery"))
+                    .state_topic(format!("{identifier}/battery")),
+            )
+        };
+        let mut bundles = vec![bundle("sensor-01"), bundle("sensor-02")];
+        DeviceBundle::route_via(&hub, &mut bundles).unwrap();
+        for bundle in bundles {
+            let json = serde_json::to_value(&bundle).unwrap();
+            assert_eq!("hub-01", json["dev"]["via_device"]);
+            for entity in bundle.into_entities() {
+                assert_eq!(
+                    "hub-01",
+                    entity.get_attributes().unwrap()["dev"]["via_device"]
+                );
+            }
+        }
+
+        let mut bundles = vec![
+            bundle("sensor-01"),
+            DeviceBundle::new(
+                Device::default().add_identifier("sensor-02").via("hub-02"),
+                Origin::new("origin"),
+            ),
+        ];
+        assert!(DeviceBundle::route_via(&hub, &mut bundles).is_err());
+        assert_eq!(None, bundles[0].device.via_device);
+    }
+
+    #[test]
+    fn can_split_device_bundle_into_entities() {
+        let device = Device::default().add_identifier("blind-01");
+        let origin = Origin::new("origin");
+        let bundle = DeviceBundle::new(device.clone(), origin.clone())
+            .add_component(
+                "blind-01_battery",
+                Sensor::default()
+                    .unique_id("blind-01_battery")
+                    .state_topic("blind-01/battery"),
+            )
+            .add_component(
+                "blind-01_cover",
+                Cover::default()
+                    .unique_id("blind-01_cover")
+                    .command_topic("blind-01/set"),
+            );
+        let expected = serde_json::to_value(&bundle).unwrap();
+
+        let entities = bundle.clone().into_entities();
+        assert_eq!(2, entities.len());
+        let recombined = entities.into_iter().fold(
+            DeviceBundle::new(device.clone(), origin.clone()),
+            |recombined, entity| {
+                let attributes = entity.get_attributes().unwrap();
+                assert_eq!(serde_json::to_value(&device).unwrap(), attributes["dev"]);
+                assert_eq!(serde_json::to_value(&origin).unwrap(), attributes["o"]);
+                let object_id = attributes["uniq_id"].as_str().unwrap().to_string();
+                recombined.add_component(object_id, entity)
+            },
+        );
+        assert_eq!(expected, serde_json::to_value(&recombined).unwrap());
+    }
+}
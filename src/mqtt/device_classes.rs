@@ -1,6 +1,8 @@
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ValveDeviceClass {
     /// Generic valve. This is the default and doesn't need to be set.
     #[serde(rename = "None")]
@@ -15,7 +17,7 @@ pub enum ValveDeviceClass {
     Gas,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum CoverDeviceClass {
     /// Generic cover. This is the default and doesn't need to be set.
     #[serde(rename = "None")]
@@ -62,7 +64,41 @@ pub enum CoverDeviceClass {
     Window,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+/// Returned when a string does not name any known device class.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownDeviceClass(pub String);
+
+impl fmt::Display for UnknownDeviceClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown device class '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownDeviceClass {}
+
+impl FromStr for CoverDeviceClass {
+    type Err = UnknownDeviceClass;
+
+    /// Parses the device class name as used in Home Assistant configuration, e.g. `garage`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "None" => Ok(CoverDeviceClass::None),
+            "awning" => Ok(CoverDeviceClass::Awning),
+            "blind" => Ok(CoverDeviceClass::Blind),
+            "curtain" => Ok(CoverDeviceClass::Curtain),
+            "damper" => Ok(CoverDeviceClass::Damper),
+            "door" => Ok(CoverDeviceClass::Door),
+            "garage" => Ok(CoverDeviceClass::Garage),
+            "gate" => Ok(CoverDeviceClass::Gate),
+            "shade" => Ok(CoverDeviceClass::Shade),
+            "shutter" => Ok(CoverDeviceClass::Shutter),
+            "window" => Ok(CoverDeviceClass::Window),
+            _ => Err(UnknownDeviceClass(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum NumberDeviceClass {
     /// Generic number. This is the default and doesn't need to be set.
     #[serde(rename = "None")]
@@ -277,7 +313,7 @@ pub enum NumberDeviceClass {
     WindSpeed,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum MediaPlayerDeviceClass {
     /// Device is a television type device.
     #[serde(rename = "tv")]
@@ -292,7 +328,7 @@ pub enum MediaPlayerDeviceClass {
     Receiver,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum BinarySensorDeviceClass {
     /// Generic on/off. This is the default and doesn't need to be set.
     #[serde(rename = "None")]
@@ -411,7 +447,53 @@ pub enum BinarySensorDeviceClass {
     Window,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+impl BinarySensorDeviceClass {
+    /// Label the frontend shows for the `on` state. Payloads are still `ON`/`OFF` regardless of the class.
+    pub fn on_label(&self) -> &'static str {
+        self.labels().0
+    }
+
+    /// Label the frontend shows for the `off` state.
+    pub fn off_label(&self) -> &'static str {
+        self.labels().1
+    }
+
+    fn labels(&self) -> (&'static str, &'static str) {
+        match self {
+            BinarySensorDeviceClass::None => ("On", "Off"),
+            BinarySensorDeviceClass::Battery => ("Low", "Normal"),
+            BinarySensorDeviceClass::BatteryCharging => ("Charging", "Not charging"),
+            BinarySensorDeviceClass::CarbonMonoxide => ("Detected", "Clear"),
+            BinarySensorDeviceClass::Cold => ("Cold", "Normal"),
+            BinarySensorDeviceClass::Connectivity => ("Connected", "Disconnected"),
+            BinarySensorDeviceClass::Door => ("Open", "Closed"),
+            BinarySensorDeviceClass::GarageDoor => ("Open", "Closed"),
+            BinarySensorDeviceClass::Gas => ("Detected", "Clear"),
+            BinarySensorDeviceClass::Heat => ("Hot", "Normal"),
+            BinarySensorDeviceClass::Light => ("Light detected", "No light"),
+            BinarySensorDeviceClass::Lock => ("Unlocked", "Locked"),
+            BinarySensorDeviceClass::Moisture => ("Wet", "Dry"),
+            BinarySensorDeviceClass::Motion => ("Detected", "Clear"),
+            BinarySensorDeviceClass::Moving => ("Moving", "Not moving"),
+            BinarySensorDeviceClass::Occupancy => ("Detected", "Clear"),
+            BinarySensorDeviceClass::Opening => ("Open", "Closed"),
+            BinarySensorDeviceClass::Plug => ("Plugged in", "Unplugged"),
+            BinarySensorDeviceClass::Power => ("Detected", "No power"),
+            BinarySensorDeviceClass::Presence => ("Home", "Away"),
+            BinarySensorDeviceClass::Problem => ("Problem", "OK"),
+            BinarySensorDeviceClass::Running => ("Running", "Not running"),
+            BinarySensorDeviceClass::Safety => ("Unsafe", "Safe"),
+            BinarySensorDeviceClass::Smoke => ("Detected", "Clear"),
+            BinarySensorDeviceClass::Sound => ("Detected", "Clear"),
+            BinarySensorDeviceClass::Tamper => ("Detected", "Clear"),
+            BinarySensorDeviceClass::Update => ("Update available", "Up-to-date"),
+            BinarySensorDeviceClass::Vibration => ("Detected", "Clear"),
+            BinarySensorDeviceClass::Window => ("Open", "Closed"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum UpdateDeviceClass {
     /// A generic software update. This is the default and doesn't need
     #[serde(rename = "None")]
@@ -422,7 +504,7 @@ pub enum UpdateDeviceClass {
     Firmware,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum HumidifierDeviceClass {
     /// Adds humidity to the air around it.
     #[serde(rename = "Humidifier")]
@@ -433,7 +515,7 @@ pub enum HumidifierDeviceClass {
     Dehumidifier,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SwitchDeviceClass {
     /// Generic switch. This is the default and doesn't need to be set.
     #[serde(rename = "None")]
@@ -448,10 +530,10 @@ pub enum SwitchDeviceClass {
     Switch,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum HomeassistantDeviceClass {}
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EventDeviceClass {
     /// Generic event. This is the default and doesn't need to be set.
     #[serde(rename = "None")]
@@ -470,7 +552,7 @@ pub enum EventDeviceClass {
     Motion,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SensorDeviceClass {
     /// Generic sensor. This is the default and doesn't need to be set.
     #[serde(rename = "None")]
@@ -697,7 +779,7 @@ pub enum SensorDeviceClass {
     WindSpeed,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ButtonDeviceClass {
     /// Generic button. This is the default and doesn't need to be set.
     #[serde(rename = "None")]
@@ -715,3 +797,29 @@ pub enum ButtonDeviceClass {
     #[serde(rename = "update")]
     Update,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_cover_device_class() {
+        assert_eq!(Ok(CoverDeviceClass::Garage), "garage".parse());
+    }
+
+    #[test]
+    fn rejects_unknown_cover_device_class() {
+        assert_eq!(
+            Err(UnknownDeviceClass("spaceship".to_string())),
+            "spaceship".parse::<CoverDeviceClass>()
+        );
+    }
+
+    #[test]
+    fn binary_sensor_labels() {
+        assert_eq!("Detected", BinarySensorDeviceClass::Motion.on_label());
+        assert_eq!("Clear", BinarySensorDeviceClass::Motion.off_label());
+        assert_eq!("Open", BinarySensorDeviceClass::Door.on_label());
+        assert_eq!("Closed", BinarySensorDeviceClass::Door.off_label());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{Entity, discovery_topic};
+use anyhow::{Result, anyhow};
+
+/// A discovery config message, ready to be published by any MQTT client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveryMessage {
+    /// The discovery topic, `<discovery_prefix>/<component>/[<node_id>/]<object_id>/config`.
+    pub topic: String,
+    /// The discovery config of the entity as JSON.
+    pub payload: String,
+    /// Whether the message should be retained. Always `true`, so Home Assistant rediscovers the entity
+    /// after a restart.
+    pub retain: bool,
+}
+
+/// Builds the discovery config messages for a set of entities, without publishing them.
+///
+/// As in [`HomeAssistantMqtt::publish_entity`](crate::HomeAssistantMqtt::publish_entity), the unique ID of
+/// each entity is used as `<object_id>`.
+#[derive(Clone)]
+pub struct DiscoveryPlan {
+    discovery_prefix: String,
+    node_id: Option<String>,
+    entities: Vec<Entity>,
+}
+
+impl DiscoveryPlan {
+    /// A plan for publishing `entities` under `discovery_prefix`, usually `homeassistant`.
+    pub fn new<S: Into<String>>(discovery_prefix: S, entities: Vec<Entity>) -> Self {
+        Self {
+            discovery_prefix: discovery_prefix.into(),
+            node_id: None,
+            entities,
+        }
+    }
+
+    /// Sets the optional `<node_id>` level of the discovery topics.
+    pub fn node_id<S: Into<String>>(mut self, node_id: S) -> Self {
+        self.node_id = Some(node_id.into());
+        self
+    }
+
+    /// Produces one retained config message per entity, in the order of the entities.
+    ///
+    /// Fails if an entity has no unique ID or can't be serialized.
+    pub fn messages(&self) -> Result<Vec<DiscoveryMessage>> {
+        self.entities
+            .iter()
+            .map(|entity| {
+                let object_id = entity.unique_id().ok_or(anyhow!(
+                    "{} entity should have a unique ID",
+                    entity.get_component_name()
+                ))?;
+                Ok(DiscoveryMessage {
+                    topic: discovery_topic(
+                        &self.discovery_prefix,
+                        entity.get_component_name(),
+                        self.node_id.as_deref(),
+                        object_id,
+                    ),
+                    payload: entity.to_ordered_json()?,
+                    retain: true,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt::{number::Number, switch::Switch};
+
+    #[test]
+    fn produces_one_retained_message_per_entity() {
+        let plan = DiscoveryPlan::new(
+            "homeassistant",
+            vec![
+                Number::default().unique_id("thermostat").into(),
+                Switch::default().unique_id("outlet").into(),
+            ],
+        )
+        .node_id("node");
+        let messages = plan.messages().unwrap();
+        assert_eq!(2, messages.len());
+        assert_eq!(
+            "homeassistant/number/node/thermostat/config",
+            messages[0].topic
+        );
+        assert_eq!("homeassistant/switch/node/outlet/config", messages[1].topic);
+        assert!(messages.iter().all(|message| message.retain));
+        assert!(messages[0].payload.contains(r#""uniq_id":"thermostat""#));
+    }
+
+    #[test]
+    fn rejects_entity_without_unique_id() {
+        let plan = DiscoveryPlan::new("homeassistant", vec![Number::default().into()]);
+        assert!(plan.messages().is_err());
+    }
+}
@@ -1,7 +1,9 @@
 use super::common::Qos;
+use super::common::validate_preset_modes;
 use super::common::{Availability, Device, EntityCategory, Origin};
-use crate::Entity;
-use serde_derive::Serialize;
+use crate::{Entity, validate_topics};
+use anyhow::Result;
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Fan"
@@ -120,7 +122,8 @@ use serde_derive::Serialize;
 /// ```
 ///
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Fan {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -128,11 +131,11 @@ pub struct Fan {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -698,3 +701,190 @@ impl From<Fan> for Entity {
         Entity::Fan(value)
     }
 }
+
+/// The direction a fan rotates in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FanDirection {
+    Forward,
+    Reverse,
+}
+
+impl Fan {
+    /// Interprets a payload received on `direction_state_topic`. Home Assistant expects `forward` or `reverse`,
+    /// so a `direction_value_template` should be used by devices reporting other payloads.
+    pub fn interpret_direction(&self, payload: &str) -> Option<FanDirection> {
+        match payload {
+            "forward" => Some(FanDirection::Forward),
+            "reverse" => Some(FanDirection::Reverse),
+            _ => None,
+        }
+    }
+
+    /// Interprets a payload received on `oscillation_state_topic` using `payload_oscillation_on`
+    /// and `payload_oscillation_off`.
+    pub fn interpret_oscillation(&self, payload: &str) -> Option<bool> {
+        if payload
+            == self
+                .payload_oscillation_on
+                .as_deref()
+                .unwrap_or("oscillate_on")
+        {
+            Some(true)
+        } else if payload
+            == self
+                .payload_oscillation_off
+                .as_deref()
+                .unwrap_or("oscillate_off")
+        {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Interprets a payload received on `preset_mode_state_topic`, returning the matching preset from
+    /// `preset_modes`. Yields `None` for the reset payload `payload_reset_preset_mode` (default `None`),
+    /// which Home Assistant uses to clear the preset, and for payloads that aren't a declared preset.
+    pub fn interpret_preset(&self, payload: &str) -> Option<&str> {
+        if payload == self.payload_reset_preset_mode.as_deref().unwrap_or("None") {
+            return None;
+        }
+        self.preset_modes
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .find(|preset| *preset == payload)
+    }
+
+    /// The `speed_range_min` (default 1) and `speed_range_max` (default 100) range, as signed 64-bit integers.
+    fn speed_range(&self) -> (i64, i64) {
+        (
+            self.speed_range_min.unwrap_or(1).into(),
+            self.speed_range_max.unwrap_or(100).into(),
+        )
+    }
+
+    /// Maps a percentage onto the speed range the way Home Assistant does before publishing it on
+    /// `percentage_command_topic`: 0 % is `speed_range_min - 1` (off), other percentages are scaled
+    /// and rounded up, so any non-zero percentage runs the fan. Percentages above 100 are clamped.
+    pub fn percentage_to_speed(&self, pct: u8) -> u16 {
+        let (min, max) = self.speed_range();
+        let states = (max - min + 1).max(0);
+        let speed = (i64::from(pct.min(100)) * states + 99) / 100 + min - 1;
+        speed.clamp(0, u16::MAX.into()) as u16
+    }
+
+    /// Maps a speed received on `percentage_state_topic` to a percentage the way Home Assistant does,
+    /// rounding down. Speeds outside the speed range are clamped to 0 % and 100 %.
+    pub fn speed_to_percentage(&self, speed: u16) -> u8 {
+        let (min, max) = self.speed_range();
+        let states = max - min + 1;
+        if states <= 0 {
+            return 0;
+        }
+        let pct = (i64::from(speed) - min + 1) * 100 / states;
+        pct.clamp(0, 100) as u8
+    }
+
+    /// Checks that `preset_modes` doesn't include the reserved `none` preset.
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        validate_preset_modes(self.preset_modes.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interprets_direction() {
+        let fan = Fan::default();
+        assert_eq!(
+            Some(FanDirection::Forward),
+            fan.interpret_direction("forward")
+        );
+        assert_eq!(
+            Some(FanDirection::Reverse),
+            fan.interpret_direction("reverse")
+        );
+        assert_eq!(None, fan.interpret_direction("fwd"));
+    }
+
+    #[test]
+    fn interprets_default_oscillation_payloads() {
+        let fan = Fan::default();
+        assert_eq!(Some(true), fan.interpret_oscillation("oscillate_on"));
+        assert_eq!(Some(false), fan.interpret_oscillation("oscillate_off"));
+        assert_eq!(None, fan.interpret_oscillation("true"));
+    }
+
+    #[test]
+    fn interprets_configured_oscillation_payloads() {
+        let fan = Fan::default()
+            .payload_oscillation_on("true")
+            .payload_oscillation_off("false");
+        assert_eq!(Some(true), fan.interpret_oscillation("true"));
+        assert_eq!(Some(false), fan.interpret_oscillation("false"));
+        assert_eq!(None, fan.interpret_oscillation("oscillate_on"));
+    }
+
+    #[test]
+    fn interprets_declared_preset() {
+        let fan = Fan::default().preset_modes(vec!["eco", "sleep"]);
+        assert_eq!(Some("sleep"), fan.interpret_preset("sleep"));
+        assert_eq!(None, fan.interpret_preset("turbo"));
+    }
+
+    #[test]
+    fn reset_payload_clears_preset() {
+        let fan = Fan::default().preset_modes(vec!["eco"]);
+        assert_eq!(None, fan.interpret_preset("None"));
+
+        let fan = fan.payload_reset_preset_mode("eco");
+        assert_eq!(None, fan.interpret_preset("eco"));
+    }
+
+    #[test]
+    fn rejects_reserved_none_preset() {
+        assert!(
+            Fan::default()
+                .preset_modes(vec!["eco", "none"])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            Fan::default()
+                .preset_modes(vec!["eco", "None"])
+                .validate()
+                .is_ok()
+        );
+        assert!(Fan::default().preset_modes(vec!["eco"]).validate().is_ok());
+    }
+
+    #[test]
+    fn maps_percentages_to_speed_range() {
+        let fan = Fan::default().speed_range_min(1).speed_range_max(10);
+        assert_eq!(0, fan.percentage_to_speed(0));
+        assert_eq!(1, fan.percentage_to_speed(1));
+        assert_eq!(5, fan.percentage_to_speed(50));
+        assert_eq!(10, fan.percentage_to_speed(100));
+        assert_eq!(10, fan.percentage_to_speed(255));
+    }
+
+    #[test]
+    fn maps_speeds_to_percentages() {
+        let fan = Fan::default().speed_range_min(1).speed_range_max(10);
+        assert_eq!(0, fan.speed_to_percentage(0));
+        assert_eq!(10, fan.speed_to_percentage(1));
+        assert_eq!(100, fan.speed_to_percentage(10));
+        assert_eq!(100, fan.speed_to_percentage(11));
+    }
+
+    #[test]
+    fn default_speed_range_is_percentage() {
+        let fan = Fan::default();
+        assert_eq!(42, fan.percentage_to_speed(42));
+        assert_eq!(42, fan.speed_to_percentage(42));
+    }
+}
@@ -3,7 +3,7 @@ use super::common::{Availability, Device, EntityCategory, Origin};
 use super::device_classes::HumidifierDeviceClass;
 use crate::Entity;
 pub use rust_decimal::Decimal;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Humidifier"
@@ -80,7 +80,8 @@ use serde_derive::Serialize;
 /// ```
 ///
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Humidifier {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -88,11 +89,11 @@ pub struct Humidifier {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -585,3 +586,53 @@ impl From<Humidifier> for Entity {
         Entity::Humidifier(value)
     }
 }
+
+/// The current action of a humidifier, as reported on `action_topic`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HumidifierAction {
+    Off,
+    Humidifying,
+    Drying,
+    Idle,
+}
+
+impl Humidifier {
+    /// Interprets a payload received on `action_topic`, after `action_template` is applied.
+    /// Payloads other than `off`, `humidifying`, `drying` and `idle` yield `None`.
+    pub fn interpret_action(&self, payload: &str) -> Option<HumidifierAction> {
+        match payload {
+            "off" => Some(HumidifierAction::Off),
+            "humidifying" => Some(HumidifierAction::Humidifying),
+            "drying" => Some(HumidifierAction::Drying),
+            "idle" => Some(HumidifierAction::Idle),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interprets_actions() {
+        let humidifier = Humidifier::default();
+        assert_eq!(
+            Some(HumidifierAction::Off),
+            humidifier.interpret_action("off")
+        );
+        assert_eq!(
+            Some(HumidifierAction::Humidifying),
+            humidifier.interpret_action("humidifying")
+        );
+        assert_eq!(
+            Some(HumidifierAction::Drying),
+            humidifier.interpret_action("drying")
+        );
+        assert_eq!(
+            Some(HumidifierAction::Idle),
+            humidifier.interpret_action("idle")
+        );
+        assert_eq!(None, humidifier.interpret_action("heating"));
+    }
+}
@@ -1,7 +1,8 @@
 use super::common::Qos;
 use super::common::{Availability, Device, EntityCategory, Origin};
-use crate::Entity;
-use serde_derive::Serialize;
+use crate::{Entity, validate_topics};
+use anyhow::{Result, bail};
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT lawn mower"
@@ -52,7 +53,8 @@ use serde_derive::Serialize;
 /// ```
 ///
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LawnMower {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -60,11 +62,11 @@ pub struct LawnMower {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -384,3 +386,50 @@ impl From<LawnMower> for Entity {
         Entity::LawnMower(value)
     }
 }
+
+impl LawnMower {
+    /// Checks that the lawn mower is actionable, i.e. has at least one of the `dock`, `pause` or `start_mowing`
+    /// command topics, and that `activity_state_topic` is set when `activity_value_template` is used.
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        if self.dock_command_topic.is_none()
+            && self.pause_command_topic.is_none()
+            && self.start_mowing_command_topic.is_none()
+        {
+            bail!(
+                "at least one of dock_command_topic, pause_command_topic or start_mowing_command_topic is required"
+            );
+        }
+        if self.activity_value_template.is_some() && self.activity_state_topic.is_none() {
+            bail!("activity_value_template requires activity_state_topic");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_lawn_mower_without_command_topics() {
+        assert!(LawnMower::default().validate().is_err());
+    }
+
+    #[test]
+    fn rejects_activity_template_without_state_topic() {
+        let lawn_mower = LawnMower::default()
+            .start_mowing_command_topic("~/start")
+            .activity_value_template("{{ value_json.activity }}");
+        assert!(lawn_mower.validate().is_err());
+    }
+
+    #[test]
+    fn accepts_actionable_lawn_mower() {
+        let lawn_mower = LawnMower::default()
+            .dock_command_topic("~/dock")
+            .activity_state_topic("~/activity")
+            .activity_value_template("{{ value_json.activity }}");
+        assert!(lawn_mower.validate().is_ok());
+    }
+}
@@ -1,7 +1,9 @@
 use super::common::Qos;
-use super::common::{Availability, Device, EntityCategory, Origin};
-use crate::Entity;
-use serde_derive::Serialize;
+use super::common::{Availability, Device, EntityCategory, OnOffPayloads, Origin};
+use crate::{Entity, validate_topics};
+use anyhow::{Result, bail};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
 
 /// ---
 /// title: "MQTT Light"
@@ -470,7 +472,8 @@ use serde_derive::Serialize;
 ///
 /// If you don't want brightness, color or effect support, just omit the corresponding configuration sections.
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Light {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -478,11 +481,11 @@ pub struct Light {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -555,7 +558,7 @@ pub struct Light {
 
     /// The list of effects the light supports.
     #[serde(rename = "fx_list", skip_serializing_if = "Option::is_none")]
-    pub effect_list: Option<Vec<String>>,
+    pub effect_list: Option<EffectList>,
 
     /// The MQTT topic subscribed to receive effect state updates.
     #[serde(rename = "fx_stat_t", skip_serializing_if = "Option::is_none")]
@@ -717,6 +720,11 @@ pub struct Light {
     #[serde(rename = "stat_val_tpl", skip_serializing_if = "Option::is_none")]
     pub state_value_template: Option<String>,
 
+    /// The color modes supported by the light. `onoff` and `brightness` must be used alone,
+    /// and `white` requires another color mode.
+    #[serde(rename = "sup_clrm", skip_serializing_if = "Option::is_none")]
+    pub supported_color_modes: Option<Vec<ColorMode>>,
+
     /// An ID that uniquely identifies this light. If two lights have the same unique ID, Home Assistant will raise an exception. Required when used with device-based discovery.
     #[serde(rename = "uniq_id", skip_serializing_if = "Option::is_none")]
     pub unique_id: Option<String>,
@@ -890,8 +898,8 @@ impl Light {
     }
 
     /// The list of effects the light supports.
-    pub fn effect_list<T: Into<String>>(mut self, effect_list: Vec<T>) -> Self {
-        self.effect_list = Some(effect_list.into_iter().map(|v| v.into()).collect());
+    pub fn effect_list(mut self, effect_list: EffectList) -> Self {
+        self.effect_list = Some(effect_list);
         self
     }
 
@@ -1018,6 +1026,13 @@ impl Light {
         self
     }
 
+    /// Sets both `payload_on` and `payload_off`.
+    pub fn on_off_payloads(mut self, payloads: OnOffPayloads) -> Self {
+        self.payload_on = Some(payloads.on);
+        self.payload_off = Some(payloads.off);
+        self
+    }
+
     /// The payload that represents disabled state.
     pub fn payload_off<T: Into<String>>(mut self, payload_off: T) -> Self {
         self.payload_off = Some(payload_off.into());
@@ -1138,6 +1153,13 @@ impl Light {
         self
     }
 
+    /// The color modes supported by the light. `onoff` and `brightness` must be used alone,
+    /// and `white` requires another color mode.
+    pub fn supported_color_modes(mut self, supported_color_modes: Vec<ColorMode>) -> Self {
+        self.supported_color_modes = Some(supported_color_modes);
+        self
+    }
+
     /// An ID that uniquely identifies this light. If two lights have the same unique ID, Home Assistant will raise an exception. Required when used with device-based discovery.
     pub fn unique_id<T: Into<String>>(mut self, unique_id: T) -> Self {
         self.unique_id = Some(unique_id.into());
@@ -1245,6 +1267,7 @@ impl Default for Light {
             schema: Default::default(),
             state_topic: Default::default(),
             state_value_template: Default::default(),
+            supported_color_modes: Default::default(),
             unique_id: Default::default(),
             white_command_topic: Default::default(),
             white_scale: Default::default(),
@@ -1261,3 +1284,348 @@ impl From<Light> for Entity {
         Entity::Light(value)
     }
 }
+
+impl Light {
+    /// Checks that `command_topic` is set and that every `*_state_topic` has its corresponding `*_command_topic`,
+    /// e.g. `brightness_state_topic` requires `brightness_command_topic`.
+    /// A mireds range requires `color_temp_command_topic` and must be ordered (`min_mireds < max_mireds`).
+    /// `supported_color_modes` must follow the rules of [ColorModeError].
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        if self.command_topic.is_empty() {
+            bail!("command_topic is required");
+        }
+        let pairs = [
+            (
+                "brightness",
+                &self.brightness_state_topic,
+                &self.brightness_command_topic,
+            ),
+            (
+                "color_temp",
+                &self.color_temp_state_topic,
+                &self.color_temp_command_topic,
+            ),
+            (
+                "effect",
+                &self.effect_state_topic,
+                &self.effect_command_topic,
+            ),
+            ("hs", &self.hs_state_topic, &self.hs_command_topic),
+            ("rgb", &self.rgb_state_topic, &self.rgb_command_topic),
+            ("rgbw", &self.rgbw_state_topic, &self.rgbw_command_topic),
+            ("rgbww", &self.rgbww_state_topic, &self.rgbww_command_topic),
+            ("xy", &self.xy_state_topic, &self.xy_command_topic),
+        ];
+        for (prefix, state_topic, command_topic) in pairs {
+            if state_topic.is_some() && command_topic.is_none() {
+                bail!("{prefix}_state_topic requires {prefix}_command_topic");
+            }
+        }
+        if (self.min_mireds.is_some() || self.max_mireds.is_some())
+            && self.color_temp_command_topic.is_none()
+        {
+            bail!("min_mireds and max_mireds require color_temp_command_topic");
+        }
+        if let (Some(min_mireds), Some(max_mireds)) = (self.min_mireds, self.max_mireds)
+            && min_mireds >= max_mireds
+        {
+            bail!("min_mireds ({min_mireds}) must be lower than max_mireds ({max_mireds})");
+        }
+        if let Some(modes) = &self.supported_color_modes {
+            for exclusive in [ColorMode::OnOff, ColorMode::Brightness] {
+                if modes.contains(&exclusive) && modes.len() > 1 {
+                    bail!(ColorModeError::NotAlone(exclusive));
+                }
+            }
+            if modes.contains(&ColorMode::White) && modes.len() == 1 {
+                bail!(ColorModeError::WhiteWithoutColorMode);
+            }
+        }
+        Ok(())
+    }
+
+    /// Clamps a brightness reported by the device to `[0, brightness_scale]`, `brightness_scale` being 255 by default.
+    pub fn clamp_brightness(&self, value: u16) -> u16 {
+        let scale = self
+            .brightness_scale
+            .unwrap_or(255)
+            .clamp(0, u16::MAX.into());
+        value.min(scale as u16)
+    }
+
+    /// Generates an `rgb_command_template` sending the channels in the given order, comma separated,
+    /// for devices that don't expect them in RGB order, e.g. `{{ green }},{{ red }},{{ blue }}` for GRB.
+    pub fn rgb_command_template_for(order: RgbOrder) -> String {
+        let channels = match order {
+            RgbOrder::Rgb => ["red", "green", "blue"],
+            RgbOrder::Rbg => ["red", "blue", "green"],
+            RgbOrder::Grb => ["green", "red", "blue"],
+            RgbOrder::Gbr => ["green", "blue", "red"],
+            RgbOrder::Brg => ["blue", "red", "green"],
+            RgbOrder::Bgr => ["blue", "green", "red"],
+        };
+        channels
+            .map(|channel| format!("{{{{ {channel} }}}}"))
+            .join(",")
+    }
+}
+
+/// The order in which a device expects the channels of an RGB color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RgbOrder {
+    Rgb,
+    Rbg,
+    Grb,
+    Gbr,
+    Brg,
+    Bgr,
+}
+
+/// A color mode of a light.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    #[serde(rename = "onoff")]
+    OnOff,
+    Brightness,
+    ColorTemp,
+    Hs,
+    Xy,
+    Rgb,
+    Rgbw,
+    Rgbww,
+    White,
+}
+
+/// A combination of `supported_color_modes` Home Assistant rejects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColorModeError {
+    /// `onoff` or `brightness` is combined with other color modes.
+    NotAlone(ColorMode),
+    /// `white` is the only color mode.
+    WhiteWithoutColorMode,
+}
+
+impl fmt::Display for ColorModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorModeError::NotAlone(mode) => {
+                write!(
+                    f,
+                    "color mode {mode:?} can't be combined with other color modes"
+                )
+            }
+            ColorModeError::WhiteWithoutColorMode => {
+                write!(f, "color mode White requires another color mode")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ColorModeError {}
+
+/// The list of effects a light supports. Every effect name is non-empty and unique.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(into = "Vec<String>", try_from = "Vec<String>")]
+pub struct EffectList(Vec<String>);
+
+impl EffectList {
+    /// Builds an effect list, rejecting empty and duplicate effect names.
+    pub fn new<T: Into<String>>(effects: Vec<T>) -> Result<Self> {
+        let mut list: Vec<String> = Vec::with_capacity(effects.len());
+        for effect in effects {
+            let effect = effect.into();
+            if effect.is_empty() {
+                bail!("effect names must not be empty");
+            }
+            if list.contains(&effect) {
+                bail!("duplicate effect '{effect}'");
+            }
+            list.push(effect);
+        }
+        Ok(Self(list))
+    }
+
+    /// The effect names, in the order they were given.
+    pub fn effects(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<String>> for EffectList {
+    type Error = anyhow::Error;
+
+    fn try_from(effects: Vec<String>) -> Result<Self> {
+        Self::new(effects)
+    }
+}
+
+impl From<EffectList> for Vec<String> {
+    fn from(effect_list: EffectList) -> Self {
+        effect_list.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn effect_list_serializes_as_string_array() {
+        let light =
+            Light::default().effect_list(EffectList::new(vec!["rainbow", "colorloop"]).unwrap());
+        assert_eq!(
+            json!(["rainbow", "colorloop"]),
+            serde_json::to_value(&light).unwrap()["fx_list"]
+        );
+    }
+
+    #[test]
+    fn effect_list_rejects_duplicates() {
+        assert!(EffectList::new(vec!["rainbow", "rainbow"]).is_err());
+    }
+
+    #[test]
+    fn effect_list_rejects_empty_names() {
+        assert!(EffectList::new(vec!["rainbow", ""]).is_err());
+    }
+
+    #[test]
+    fn rejects_light_without_command_topic() {
+        assert!(Light::default().validate().is_err());
+    }
+
+    #[test]
+    fn rejects_state_topic_without_command_topic() {
+        let light = Light::default().command_topic("~/set");
+        let mismatches = [
+            light.clone().brightness_state_topic("~/brightness"),
+            light.clone().color_temp_state_topic("~/color_temp"),
+            light.clone().effect_state_topic("~/effect"),
+            light.clone().hs_state_topic("~/hs"),
+            light.clone().rgb_state_topic("~/rgb"),
+            light.clone().rgbw_state_topic("~/rgbw"),
+            light.clone().rgbww_state_topic("~/rgbww"),
+            light.clone().xy_state_topic("~/xy"),
+        ];
+        for mismatch in mismatches {
+            assert!(mismatch.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn accepts_state_topic_with_command_topic() {
+        let light = Light::default()
+            .command_topic("~/set")
+            .brightness_state_topic("~/brightness")
+            .brightness_command_topic("~/brightness/set");
+        assert!(light.validate().is_ok());
+    }
+
+    #[test]
+    fn accepts_ordered_mireds_range() {
+        let light = Light::default()
+            .command_topic("~/set")
+            .color_temp_command_topic("~/color_temp/set")
+            .min_mireds(153)
+            .max_mireds(334);
+        assert!(light.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_unordered_mireds_range() {
+        let light = Light::default()
+            .command_topic("~/set")
+            .color_temp_command_topic("~/color_temp/set")
+            .min_mireds(334)
+            .max_mireds(153);
+        assert!(light.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_mireds_range_without_color_temp_command_topic() {
+        let light = Light::default().command_topic("~/set").min_mireds(153);
+        assert!(light.validate().is_err());
+    }
+
+    #[test]
+    fn clamps_brightness_to_default_scale() {
+        let light = Light::default();
+        assert_eq!(0, light.clamp_brightness(0));
+        assert_eq!(255, light.clamp_brightness(255));
+        assert_eq!(255, light.clamp_brightness(256));
+    }
+
+    #[test]
+    fn clamps_brightness_to_custom_scale() {
+        let light = Light::default().brightness_scale(4095);
+        assert_eq!(4095, light.clamp_brightness(4095));
+        assert_eq!(4095, light.clamp_brightness(u16::MAX));
+        assert_eq!(1000, light.clamp_brightness(1000));
+    }
+
+    fn color_mode_error(light: Light) -> ColorModeError {
+        light
+            .command_topic("~/set")
+            .validate()
+            .unwrap_err()
+            .downcast::<ColorModeError>()
+            .unwrap()
+    }
+
+    #[test]
+    fn rejects_onoff_with_other_color_modes() {
+        let light = Light::default().supported_color_modes(vec![ColorMode::OnOff, ColorMode::Rgb]);
+        assert_eq!(
+            ColorModeError::NotAlone(ColorMode::OnOff),
+            color_mode_error(light)
+        );
+    }
+
+    #[test]
+    fn rejects_brightness_with_other_color_modes() {
+        let light = Light::default()
+            .supported_color_modes(vec![ColorMode::ColorTemp, ColorMode::Brightness]);
+        assert_eq!(
+            ColorModeError::NotAlone(ColorMode::Brightness),
+            color_mode_error(light)
+        );
+    }
+
+    #[test]
+    fn rejects_white_without_color_mode() {
+        let light = Light::default().supported_color_modes(vec![ColorMode::White]);
+        assert_eq!(
+            ColorModeError::WhiteWithoutColorMode,
+            color_mode_error(light)
+        );
+    }
+
+    #[test]
+    fn accepts_valid_color_modes() {
+        let light = Light::default()
+            .command_topic("~/set")
+            .supported_color_modes(vec![ColorMode::Rgb, ColorMode::White]);
+        assert!(light.validate().is_ok());
+        assert_eq!(
+            serde_json::json!(["rgb", "white"]),
+            serde_json::to_value(&light).unwrap()["sup_clrm"]
+        );
+        let light = light.supported_color_modes(vec![ColorMode::OnOff]);
+        assert!(light.validate().is_ok());
+    }
+
+    #[test]
+    fn generates_rgb_command_templates() {
+        assert_eq!(
+            "{{ red }},{{ green }},{{ blue }}",
+            Light::rgb_command_template_for(RgbOrder::Rgb)
+        );
+        assert_eq!(
+            "{{ green }},{{ red }},{{ blue }}",
+            Light::rgb_command_template_for(RgbOrder::Grb)
+        );
+    }
+}
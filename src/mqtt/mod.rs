@@ -1,5 +1,7 @@
 pub mod common;
+pub mod device_bundle;
 pub mod device_classes;
+pub mod discovery_plan;
 pub mod units;
 
 pub mod alarm_control_panel;
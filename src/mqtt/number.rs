@@ -1,10 +1,15 @@
 use super::common::Qos;
-use super::common::{Availability, Device, EntityCategory, Origin};
+use super::common::StateClass;
+use super::common::TemperatureUnit;
+use super::common::{Availability, Device, Encoding, EntityCategory, Origin};
 use super::device_classes::NumberDeviceClass;
-use super::units::Unit;
-use crate::Entity;
+use super::units::{TempUnit, Unit};
+use crate::{Entity, validate_topics};
+use anyhow::{Result, bail};
+use ha_mqtt_discovery_macros::ha_entity;
 pub use rust_decimal::Decimal;
-use serde_derive::Serialize;
+use rust_decimal::prelude::ToPrimitive;
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Number"
@@ -33,19 +38,21 @@ use serde_derive::Serialize;
 /// ⚠ Important\
 /// Make sure that your topic matches exactly. `some-topic/` and `some-topic` are different topics.
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[ha_entity]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Number {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
-    #[serde(rename = "~", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -53,103 +60,109 @@ pub struct Number {
     pub availability: Availability,
 
     /// The category of the entity. (optional, default: None)
-    #[serde(rename = "ent_cat", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub entity_category: Option<EntityCategory>,
 
     /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) to generate the payload to send to `command_topic`.
-    #[serde(rename = "cmd_tpl", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub command_template: Option<String>,
 
     /// The MQTT topic to publish commands to change the number.
-    #[serde(rename = "cmd_t")]
     pub command_topic: String,
 
     /// The [type/class](/integrations/number/#device-class) of the number. The `device_class` can be `null`.
-    #[serde(rename = "dev_cla", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub device_class: Option<NumberDeviceClass>,
 
     /// Flag which defines if the entity should be enabled when first added.
-    #[serde(rename = "en", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub enabled_by_default: Option<bool>,
 
     /// The encoding of the payloads received and published messages. Set to `""` to disable decoding of incoming payload.
-    #[serde(rename = "e", skip_serializing_if = "Option::is_none")]
-    pub encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
 
     /// Picture URL for the entity.
-    #[serde(rename = "ent_pic", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub entity_picture: Option<String>,
 
     /// [Icon](/docs/configuration/customizing-devices/#icon) for the entity.
-    #[serde(rename = "ic", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub icon: Option<String>,
 
     /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) to extract the JSON dictionary from messages received on the `json_attributes_topic`.
-    #[serde(rename = "json_attr_tpl", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub json_attributes_template: Option<String>,
 
     /// The MQTT topic subscribed to receive a JSON dictionary payload and then set as number attributes. Implies `force_update` of the current number state when a message is received on this topic.
-    #[serde(rename = "json_attr_t", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub json_attributes_topic: Option<String>,
 
     /// Maximum value.
-    #[serde(rename = "max", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max: Option<Decimal>,
 
     /// Minimum value.
-    #[serde(rename = "min", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub min: Option<Decimal>,
 
     /// Control how the number should be displayed in the UI. Can be set to `box` or `slider` to force a display mode.
-    #[serde(rename = "mode", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub mode: Option<String>,
 
     /// The name of the Number. Can be set to `null` if only the device name is relevant.
-    #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 
     /// Used instead of `name` for automatic generation of `entity_id`
-    #[serde(rename = "obj_id", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub object_id: Option<String>,
 
     /// Flag that defines if number works in optimistic mode.
-    #[serde(rename = "opt", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub optimistic: Option<bool>,
 
     /// A special payload that resets the state to `unknown` when received on the `state_topic`.
-    #[serde(rename = "pl_rst", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub payload_reset: Option<String>,
 
     /// Must be `number`. Only allowed and required in [MQTT auto discovery device messages](/integrations/mqtt/#device-discovery-payload).
-    #[serde(rename = "platform")]
     pub platform: String,
 
     /// The maximum QoS level to be used when receiving and publishing messages.
-    #[serde(rename = "qos", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub qos: Option<Qos>,
 
     /// If the published message should have the retain flag on or not.
-    #[serde(rename = "ret", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub retain: Option<bool>,
 
+    /// The [state_class](https://developers.home-assistant.io/docs/core/entity/sensor#available-state-classes) of the number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_class: Option<StateClass>,
+
     /// The MQTT topic subscribed to receive number values. An empty payload is ignored.
-    #[serde(rename = "stat_t", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub state_topic: Option<String>,
 
     /// Step value. Smallest value `0.001`.
-    #[serde(rename = "step", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub step: Option<Decimal>,
 
+    /// The unit of measurement Home Assistant should display the value in, if different from `unit_of_measurement`.
+    #[serde(rename = "sug_unit_of_meas", skip_serializing_if = "Option::is_none")]
+    pub suggested_unit_of_measurement: Option<Unit>,
+
     /// An ID that uniquely identifies this Number. If two Numbers have the same unique ID Home Assistant will raise an exception. Required when used with device-based discovery.
-    #[serde(rename = "uniq_id", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub unique_id: Option<String>,
 
     /// Defines the unit of measurement of the sensor, if any. The `unit_of_measurement` can be `null`.
-    #[serde(rename = "unit_of_meas", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub unit_of_measurement: Option<Unit>,
 
     /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) to extract the value.
-    #[serde(rename = "val_tpl", skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub value_template: Option<String>,
 }
 
@@ -185,6 +198,12 @@ impl Number {
         self
     }
 
+    /// Checks availability with a single topic and the default `online` and `offline` payloads.
+    pub fn availability_topic<T: Into<String>>(mut self, availability_topic: T) -> Self {
+        self.availability = Availability::single_topic(&availability_topic.into());
+        self
+    }
+
     /// Defines a [template](/docs/configuration/templating/#using-templates-with-the-mqtt-integration) to generate the payload to send to `command_topic`.
     pub fn command_template<T: Into<String>>(mut self, command_template: T) -> Self {
         self.command_template = Some(command_template.into());
@@ -210,7 +229,7 @@ impl Number {
     }
 
     /// The encoding of the payloads received and published messages. Set to `""` to disable decoding of incoming payload.
-    pub fn encoding<T: Into<String>>(mut self, encoding: T) -> Self {
+    pub fn encoding<T: Into<Encoding>>(mut self, encoding: T) -> Self {
         self.encoding = Some(encoding.into());
         self
     }
@@ -302,6 +321,12 @@ impl Number {
         self
     }
 
+    /// The [state_class](https://developers.home-assistant.io/docs/core/entity/sensor#available-state-classes) of the number.
+    pub fn state_class(mut self, state_class: StateClass) -> Self {
+        self.state_class = Some(state_class);
+        self
+    }
+
     /// The MQTT topic subscribed to receive number values. An empty payload is ignored.
     pub fn state_topic<T: Into<String>>(mut self, state_topic: T) -> Self {
         self.state_topic = Some(state_topic.into());
@@ -314,6 +339,15 @@ impl Number {
         self
     }
 
+    /// The unit of measurement Home Assistant should display the value in, if different from `unit_of_measurement`.
+    pub fn suggested_unit_of_measurement<T: Into<Unit>>(
+        mut self,
+        suggested_unit_of_measurement: T,
+    ) -> Self {
+        self.suggested_unit_of_measurement = Some(suggested_unit_of_measurement.into());
+        self
+    }
+
     /// An ID that uniquely identifies this Number. If two Numbers have the same unique ID Home Assistant will raise an exception. Required when used with device-based discovery.
     pub fn unique_id<T: Into<String>>(mut self, unique_id: T) -> Self {
         self.unique_id = Some(unique_id.into());
@@ -360,8 +394,10 @@ impl Default for Number {
             platform: "number".to_string(),
             qos: Default::default(),
             retain: Default::default(),
+            state_class: Default::default(),
             state_topic: Default::default(),
             step: Default::default(),
+            suggested_unit_of_measurement: Default::default(),
             unique_id: Default::default(),
             unit_of_measurement: Default::default(),
             value_template: Default::default(),
@@ -374,3 +410,432 @@ impl From<Number> for Entity {
         Entity::Number(value)
     }
 }
+
+/// How a number is displayed in the UI.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayMode {
+    /// An input box.
+    Box,
+    /// A slider.
+    Slider,
+}
+
+impl From<DisplayMode> for String {
+    fn from(value: DisplayMode) -> Self {
+        match value {
+            DisplayMode::Box => "box",
+            DisplayMode::Slider => "slider",
+        }
+        .to_string()
+    }
+}
+
+impl Number {
+    /// Checks that `suggested_unit_of_measurement` is compatible with `device_class`, and that a command-only number,
+    /// without `state_topic`, sets `optimistic` to `true`, as Home Assistant always runs it optimistically.
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        if let (Some(unit), Some(device_class)) =
+            (&self.suggested_unit_of_measurement, &self.device_class)
+            && !unit.compatible_device_classes().contains(device_class)
+        {
+            bail!(
+                "suggested_unit_of_measurement {unit:?} isn't compatible with device_class {device_class:?}"
+            );
+        }
+        if self.state_topic.is_none() && self.optimistic != Some(true) {
+            bail!("a number without state_topic is always optimistic, optimistic must be true");
+        }
+        Ok(())
+    }
+
+    /// Whether `payload` received on `state_topic` is the reset payload, which resets the number to unknown.
+    /// Defaults to `None` when `payload_reset` isn't set, as in Home Assistant.
+    pub fn is_reset_payload(&self, payload: &str) -> bool {
+        payload == self.payload_reset.as_deref().unwrap_or("None")
+    }
+
+    /// Clamps `value` to `[min, max]` and rounds it to the nearest `step` boundary counted from `min`,
+    /// as the Home Assistant UI would send it. Unset `min`, `max` and `step` take the defaults of 1, 100 and 1.
+    pub fn snap(&self, value: f64) -> f64 {
+        let min = self.min.unwrap_or(Decimal::ONE).to_f64().unwrap_or(1.0);
+        let max = self
+            .max
+            .unwrap_or(Decimal::ONE_HUNDRED)
+            .to_f64()
+            .unwrap_or(100.0);
+        let step = self.step.unwrap_or(Decimal::ONE).to_f64().unwrap_or(1.0);
+        let value = value.clamp(min, max);
+        if step <= 0.0 {
+            return value;
+        }
+        let snapped = min + ((value - min) / step).round() * step;
+        snapped.min(max)
+    }
+
+    /// The display mode Home Assistant picks for the `auto` mode: a slider when the range has at most 256 steps,
+    /// a box otherwise. Unset `min`, `max` and `step` take Home Assistant's defaults of 1, 100 and 1.
+    ///
+    /// Can be used to set `mode` explicitly, e.g. `number.mode(number.auto_mode())`.
+    pub fn auto_mode(&self) -> DisplayMode {
+        let min = self.min.unwrap_or(Decimal::ONE);
+        let max = self.max.unwrap_or(Decimal::ONE_HUNDRED);
+        let step = self.step.unwrap_or(Decimal::ONE);
+        if step > Decimal::ZERO && (max - min) / step <= Decimal::from(256) {
+            DisplayMode::Slider
+        } else {
+            DisplayMode::Box
+        }
+    }
+
+    /// Converts `min`, `max` and `step` from the current temperature `unit_of_measurement` to `unit`,
+    /// and sets `unit_of_measurement` accordingly.
+    ///
+    /// Fails if the current unit of measurement isn't a temperature unit.
+    pub fn convert_temperature_to(mut self, unit: TemperatureUnit) -> Result<Self> {
+        let from = match &self.unit_of_measurement {
+            Some(Unit::Temperature(from)) => from.clone(),
+            _ => bail!("unit_of_measurement should be a temperature unit"),
+        };
+        let to = match unit {
+            TemperatureUnit::Celcius => TempUnit::Celsius,
+            TemperatureUnit::Fahrenheit => TempUnit::TempFahrenheit,
+        };
+        // Temperatures are converted through Celsius. Steps are differences, so they are only scaled.
+        let scale = |unit: &TempUnit| match unit {
+            TempUnit::TempFahrenheit => Decimal::from(9) / Decimal::from(5),
+            TempUnit::Celsius | TempUnit::TempKelvin => Decimal::ONE,
+        };
+        let offset = |unit: &TempUnit| match unit {
+            TempUnit::Celsius => Decimal::ZERO,
+            TempUnit::TempFahrenheit => Decimal::from(32),
+            TempUnit::TempKelvin => Decimal::new(27315, 2),
+        };
+        let convert = |value: Decimal| {
+            ((value - offset(&from)) / scale(&from) * scale(&to) + offset(&to)).normalize()
+        };
+        self.min = self.min.map(convert);
+        self.max = self.max.map(convert);
+        self.step = self
+            .step
+            .map(|step| (step / scale(&from) * scale(&to)).normalize());
+        self.unit_of_measurement = Some(Unit::Temperature(to));
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt::common::{AvailabilityCheck, EntityCategory};
+    use crate::mqtt::units::PercentageUnit;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn flattened_availability_does_not_collide_with_number_fields() {
+        let number = Number::default()
+            .topic_prefix("prefix")
+            .origin(Origin::new("origin"))
+            .device(Device::default().add_identifier("device"))
+            .availability(
+                Availability::any(vec![AvailabilityCheck::topic("~/availability")])
+                    .expire_after(60),
+            )
+            .entity_category(EntityCategory::Config)
+            .command_template("{{ value }}")
+            .command_topic("~/set")
+            .device_class(NumberDeviceClass::Temperature)
+            .enabled_by_default(true)
+            .encoding("utf-8")
+            .entity_picture("https://picture")
+            .icon("mdi:thermometer")
+            .json_attributes_template("{{ value_json }}")
+            .json_attributes_topic("~/attributes")
+            .max(dec!(10))
+            .min(dec!(-10))
+            .mode("box")
+            .name("number")
+            .object_id("number")
+            .optimistic(true)
+            .payload_reset("None")
+            .qos(Qos::AtLeastOnce)
+            .retain(true)
+            .state_class(StateClass::Measurement)
+            .state_topic("~/state")
+            .step(dec!(0.5))
+            .unique_id("number")
+            .unit_of_measurement(Unit::Temperature(TempUnit::Celsius))
+            .value_template("{{ value }}");
+
+        let json = serde_json::to_value(&number).unwrap();
+        let keys: BTreeSet<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let expected: BTreeSet<&str> = [
+            "~",
+            "o",
+            "dev",
+            "avty_mode",
+            "avty",
+            "exp_aft",
+            "ent_cat",
+            "cmd_tpl",
+            "cmd_t",
+            "dev_cla",
+            "en",
+            "e",
+            "ent_pic",
+            "ic",
+            "json_attr_tpl",
+            "json_attr_t",
+            "max",
+            "min",
+            "mode",
+            "name",
+            "obj_id",
+            "opt",
+            "pl_rst",
+            "platform",
+            "qos",
+            "ret",
+            "stat_cla",
+            "stat_t",
+            "step",
+            "uniq_id",
+            "unit_of_meas",
+            "val_tpl",
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(expected, keys);
+    }
+
+    #[test]
+    fn ha_entity_renames_match_hand_written_abbreviations() {
+        let number = Number::default()
+            .topic_prefix("prefix")
+            .command_template("{{ value }}")
+            .command_topic("~/set")
+            .device_class(NumberDeviceClass::Temperature)
+            .enabled_by_default(true)
+            .encoding("utf-8")
+            .entity_picture("https://picture")
+            .icon("mdi:thermometer")
+            .json_attributes_template("{{ value_json }}")
+            .json_attributes_topic("~/attributes")
+            .object_id("number")
+            .optimistic(true)
+            .payload_reset("None")
+            .retain(true)
+            .state_topic("~/state")
+            .unique_id("number")
+            .unit_of_measurement(Unit::Temperature(TempUnit::Celsius))
+            .value_template("{{ value }}");
+
+        let json = serde_json::to_value(&number).unwrap();
+        for (long, abbreviation) in [
+            ("topic_prefix", "~"),
+            ("command_template", "cmd_tpl"),
+            ("command_topic", "cmd_t"),
+            ("device_class", "dev_cla"),
+            ("enabled_by_default", "en"),
+            ("encoding", "e"),
+            ("entity_picture", "ent_pic"),
+            ("icon", "ic"),
+            ("json_attributes_template", "json_attr_tpl"),
+            ("json_attributes_topic", "json_attr_t"),
+            ("object_id", "obj_id"),
+            ("optimistic", "opt"),
+            ("payload_reset", "pl_rst"),
+            ("retain", "ret"),
+            ("state_topic", "stat_t"),
+            ("unique_id", "uniq_id"),
+            ("unit_of_measurement", "unit_of_meas"),
+            ("value_template", "val_tpl"),
+        ] {
+            assert!(
+                json.get(abbreviation).is_some(),
+                "{long} is not renamed to {abbreviation}"
+            );
+            assert!(
+                json.get(long).is_none(),
+                "{long} is serialized unabbreviated"
+            );
+        }
+        assert_eq!(number, serde_json::from_value(json).unwrap());
+    }
+
+    #[test]
+    fn command_template_is_omitted_when_unset() {
+        let json = serde_json::to_value(Number::default().command_topic("~/set")).unwrap();
+        assert!(json.get("cmd_tpl").is_none());
+    }
+
+    #[test]
+    fn optimistic_and_retain_are_omitted_when_unset() {
+        let json = serde_json::to_value(Number::default().command_topic("~/set")).unwrap();
+        assert!(json.get("opt").is_none());
+        assert!(json.get("ret").is_none());
+    }
+
+    #[test]
+    fn topic_prefix_is_omitted_when_unset() {
+        let json = serde_json::to_value(Number::default().command_topic("~/set")).unwrap();
+        assert!(json.get("~").is_none());
+    }
+
+    #[test]
+    fn can_set_single_availability_topic() {
+        let number = Number::default()
+            .command_topic("~/set")
+            .availability_topic("~/availability");
+        let json = serde_json::to_value(&number).unwrap();
+        assert!(json.get("avty_mode").is_none());
+        assert_eq!(json!([{ "t": "~/availability" }]), json["avty"]);
+    }
+
+    #[test]
+    fn can_convert_celsius_range_to_fahrenheit() {
+        let number = Number::default()
+            .min(dec!(0))
+            .max(dec!(100))
+            .step(dec!(0.5))
+            .unit_of_measurement(Unit::Temperature(TempUnit::Celsius))
+            .convert_temperature_to(TemperatureUnit::Fahrenheit)
+            .unwrap();
+        assert_eq!(Some(dec!(32)), number.min);
+        assert_eq!(Some(dec!(212)), number.max);
+        assert_eq!(Some(dec!(0.9)), number.step);
+        assert_eq!(
+            Some(Unit::Temperature(TempUnit::TempFahrenheit)),
+            number.unit_of_measurement
+        );
+    }
+
+    #[test]
+    fn temperature_conversion_requires_temperature_unit() {
+        let number = Number::default().max(dec!(100));
+        assert!(
+            number
+                .convert_temperature_to(TemperatureUnit::Fahrenheit)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_json_attributes_template_without_topic() {
+        let number = Number::default()
+            .state_topic("~/state")
+            .json_attributes_template("{{ value_json }}");
+        assert!(Entity::from(number.clone()).validate().is_err());
+        let number = number.json_attributes_topic("~/attributes");
+        assert!(Entity::from(number).validate().is_ok());
+    }
+
+    #[test]
+    fn recognizes_nan_reset_payload() {
+        let number = Number::default().payload_reset("NaN");
+        assert!(number.is_reset_payload("NaN"));
+        assert!(!number.is_reset_payload("None"));
+    }
+
+    #[test]
+    fn numbers_are_not_reset_payloads() {
+        let number = Number::default().payload_reset("NaN");
+        assert!(!number.is_reset_payload("21.5"));
+        assert!(!Number::default().is_reset_payload("0"));
+        assert!(Number::default().is_reset_payload("None"));
+    }
+
+    #[test]
+    fn serializes_state_class() {
+        for (state_class, expected) in [
+            (StateClass::Measurement, "measurement"),
+            (StateClass::Total, "total"),
+            (StateClass::TotalIncreasing, "total_increasing"),
+        ] {
+            let number = Number::default().state_class(state_class);
+            let json = serde_json::to_value(&number).unwrap();
+            assert_eq!(json!(expected), json["stat_cla"]);
+        }
+    }
+
+    #[test]
+    fn serializes_suggested_unit_of_measurement() {
+        let number = Number::default()
+            .state_topic("~/state")
+            .device_class(NumberDeviceClass::Temperature)
+            .unit_of_measurement(TempUnit::Celsius)
+            .suggested_unit_of_measurement(TempUnit::TempFahrenheit);
+        let json = serde_json::to_value(&number).unwrap();
+        assert_eq!(json!("°F"), json["sug_unit_of_meas"]);
+        assert!(number.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_suggested_unit_incompatible_with_device_class() {
+        let number = Number::default()
+            .device_class(NumberDeviceClass::Temperature)
+            .suggested_unit_of_measurement(PercentageUnit::Percentage);
+        assert!(number.validate().is_err());
+    }
+
+    #[test]
+    fn auto_mode_uses_box_for_wide_range() {
+        let number = Number::default().min(dec!(0)).max(dec!(1000));
+        assert_eq!(DisplayMode::Box, number.auto_mode());
+        let number = number.clone().mode(number.auto_mode());
+        assert_eq!(Some("box".to_string()), number.mode);
+    }
+
+    #[test]
+    fn auto_mode_uses_slider_for_narrow_range() {
+        let number = Number::default().min(dec!(0)).max(dec!(10));
+        assert_eq!(DisplayMode::Slider, number.auto_mode());
+        assert_eq!(DisplayMode::Slider, Number::default().auto_mode());
+    }
+
+    #[test]
+    fn raw_encoding_serializes_as_empty_string() {
+        let json = serde_json::to_value(Number::default().encoding(Encoding::Raw)).unwrap();
+        assert_eq!(json!(""), json["e"]);
+        let json = serde_json::to_value(Number::default().encoding("ascii")).unwrap();
+        assert_eq!(json!("ascii"), json["e"]);
+    }
+
+    #[test]
+    fn snaps_value_between_steps() {
+        let number = Number::default().min(dec!(0)).max(dec!(10)).step(dec!(0.5));
+        assert_eq!(2.5, number.snap(2.6));
+        assert_eq!(3.0, number.snap(2.8));
+    }
+
+    #[test]
+    fn clamps_out_of_range_values() {
+        let number = Number::default().min(dec!(0)).max(dec!(10)).step(dec!(0.5));
+        assert_eq!(0.0, number.snap(-3.0));
+        assert_eq!(10.0, number.snap(12.0));
+        assert_eq!(1.0, Number::default().snap(0.0));
+    }
+
+    #[test]
+    fn accepts_command_only_number() {
+        let number = Number::default().command_topic("thermostat/target/set");
+        assert!(number.clone().validate().is_err());
+        assert!(number.optimistic(true).validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_non_optimistic_command_only_number() {
+        let number = Number::default()
+            .command_topic("thermostat/target/set")
+            .optimistic(false);
+        assert!(number.clone().validate().is_err());
+        assert!(number.state_topic("thermostat/target").validate().is_ok());
+    }
+}
@@ -1,7 +1,8 @@
 use super::common::Qos;
 use super::common::{Availability, Device, EntityCategory, Origin};
-use crate::Entity;
-use serde_derive::Serialize;
+use crate::{Entity, validate_topics};
+use anyhow::{Result, bail};
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Select"
@@ -34,7 +35,8 @@ use serde_derive::Serialize;
 /// ⚠ Important\
 /// Make sure that your topic matches exactly. `some-topic/` and `some-topic` are different topics.
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Select {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -42,11 +44,11 @@ pub struct Select {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -309,3 +311,72 @@ impl From<Select> for Entity {
         Entity::Select(value)
     }
 }
+
+/// The maximum number of options accepted by [Select::validate].
+///
+/// Home Assistant doesn't enforce a count itself, but the options are stored as a state attribute, and the recorder
+/// drops attributes larger than 16 KiB, so very long lists don't survive a restart.
+pub const MAX_SELECT_OPTIONS: usize = 256;
+
+impl Select {
+    /// Checks that no option is an empty string, which Home Assistant rejects, and that there are at most
+    /// [MAX_SELECT_OPTIONS] options.
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        if self.options.iter().any(String::is_empty) {
+            bail!("options must not contain an empty string");
+        }
+        if self.options.len() > MAX_SELECT_OPTIONS {
+            bail!(
+                "{} options exceed the limit of {MAX_SELECT_OPTIONS}",
+                self.options.len()
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the declared option exactly matching `payload`, if any.
+    ///
+    /// The payload is compared as received, so a `value_template` is not applied.
+    pub fn interpret(&self, payload: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .map(String::as_str)
+            .find(|option| *option == payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interprets_declared_option() {
+        let select = Select::default().options(vec!["low", "high"]);
+        assert_eq!(Some("high"), select.interpret("high"));
+    }
+
+    #[test]
+    fn ignores_undeclared_option() {
+        let select = Select::default().options(vec!["low", "high"]);
+        assert_eq!(None, select.interpret("HIGH"));
+    }
+
+    #[test]
+    fn rejects_empty_option() {
+        let select = Select::default().options(vec!["low", ""]);
+        assert!(select.validate().is_err());
+    }
+
+    #[test]
+    fn accepts_non_empty_options() {
+        assert!(Select::default().validate().is_ok());
+        assert!(Select::default().options(vec!["low"]).validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_too_many_options() {
+        let options: Vec<String> = (0..=MAX_SELECT_OPTIONS).map(|i| i.to_string()).collect();
+        assert!(Select::default().options(options).validate().is_err());
+    }
+}
@@ -1,10 +1,11 @@
 use super::common::Qos;
-use super::common::SensorStateClass;
-use super::common::{Availability, Device, EntityCategory, Origin};
+use super::common::StateClass;
+use super::common::{Availability, Device, EntityCategory, Nullable, Origin};
 use super::device_classes::SensorDeviceClass;
 use super::units::Unit;
-use crate::Entity;
-use serde_derive::Serialize;
+use crate::{Entity, validate_topics};
+use anyhow::{Result, bail};
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Sensor"
@@ -287,7 +288,8 @@ use serde_derive::Serialize;
 ///       state_topic: "home/bathroom/analog/brightness"
 /// ```
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Sensor {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -295,11 +297,11 @@ pub struct Sensor {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -311,8 +313,8 @@ pub struct Sensor {
     pub entity_category: Option<EntityCategory>,
 
     /// The [type/class](/integrations/sensor/#device-class) of the sensor to set the icon in the frontend. The `device_class` can be `null`.
-    #[serde(rename = "dev_cla", skip_serializing_if = "Option::is_none")]
-    pub device_class: Option<SensorDeviceClass>,
+    #[serde(rename = "dev_cla", skip_serializing_if = "Nullable::is_unset")]
+    pub device_class: Nullable<SensorDeviceClass>,
 
     /// Flag which defines if the entity should be enabled when first added.
     #[serde(rename = "en", skip_serializing_if = "Option::is_none")]
@@ -368,7 +370,7 @@ pub struct Sensor {
 
     /// The [state_class](https://developers.home-assistant.io/docs/core/entity/sensor#available-state-classes) of the sensor.
     #[serde(rename = "stat_cla", skip_serializing_if = "Option::is_none")]
-    pub state_class: Option<SensorStateClass>,
+    pub state_class: Option<StateClass>,
 
     /// The MQTT topic subscribed to receive sensor values. If `device_class`, `state_class`, `unit_of_measurement` or `suggested_display_precision` is set, and a numeric value is expected, an empty value `''` will be ignored and will not update the state, a `'null'` value will set the sensor to an `unknown` state. The `device_class` can be `null`.
     #[serde(rename = "stat_t")]
@@ -425,7 +427,13 @@ impl Sensor {
 
     /// The [type/class](/integrations/sensor/#device-class) of the sensor to set the icon in the frontend. The `device_class` can be `null`.
     pub fn device_class(mut self, device_class: SensorDeviceClass) -> Self {
-        self.device_class = Some(device_class);
+        self.device_class = Nullable::Value(device_class);
+        self
+    }
+
+    /// Explicitly sets the `device_class` to `null`, overriding the class Home Assistant would infer.
+    pub fn null_device_class(mut self) -> Self {
+        self.device_class = Nullable::Null;
         self
     }
 
@@ -514,7 +522,7 @@ impl Sensor {
     }
 
     /// The [state_class](https://developers.home-assistant.io/docs/core/entity/sensor#available-state-classes) of the sensor.
-    pub fn state_class<T: Into<SensorStateClass>>(mut self, state_class: T) -> Self {
+    pub fn state_class<T: Into<StateClass>>(mut self, state_class: T) -> Self {
         self.state_class = Some(state_class.into());
         self
     }
@@ -587,3 +595,119 @@ impl From<Sensor> for Entity {
         Entity::Sensor(value)
     }
 }
+
+impl Sensor {
+    /// Checks that `options` is a non-empty list, only used with the `enum` device class
+    /// and not together with `state_class` or `unit_of_measurement`,
+    /// that `state_class` isn't used with the `date` and `timestamp` device classes, which aren't numeric,
+    /// and that `last_reset_value_template` is only used with the `total` state class.
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        if let Some(options) = &self.options {
+            if options.is_empty() {
+                bail!("options must not be empty");
+            }
+            if self.device_class != Nullable::Value(SensorDeviceClass::Enum) {
+                bail!("options require the enum device class");
+            }
+            if self.state_class.is_some() || self.unit_of_measurement.is_some() {
+                bail!("options cannot be used together with state_class or unit_of_measurement");
+            }
+        }
+        if let (
+            Some(state_class),
+            Nullable::Value(
+                device_class @ (SensorDeviceClass::Date | SensorDeviceClass::Timestamp),
+            ),
+        ) = (&self.state_class, &self.device_class)
+        {
+            bail!(
+                "state_class {state_class:?} cannot be used with the {device_class:?} device class"
+            );
+        }
+        if self.last_reset_value_template.is_some() && self.state_class != Some(StateClass::Total) {
+            bail!("last_reset_value_template requires the total state class");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_enum_sensor_with_options() {
+        let sensor = Sensor::default()
+            .device_class(SensorDeviceClass::Enum)
+            .options(vec!["idle", "running"]);
+        assert!(sensor.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_options_without_enum_device_class() {
+        let sensor = Sensor::default()
+            .device_class(SensorDeviceClass::Temperature)
+            .options(vec!["idle", "running"]);
+        assert!(sensor.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_empty_options() {
+        let sensor = Sensor::default()
+            .device_class(SensorDeviceClass::Enum)
+            .options(Vec::<String>::new());
+        assert!(sensor.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_options_with_state_class() {
+        let sensor = Sensor::default()
+            .device_class(SensorDeviceClass::Enum)
+            .options(vec!["idle", "running"])
+            .state_class(StateClass::Measurement);
+        assert!(sensor.validate().is_err());
+    }
+
+    #[test]
+    fn can_serialize_last_reset_value_template() {
+        let sensor = Sensor::default()
+            .state_class(StateClass::Total)
+            .last_reset_value_template("{{ value_json.last_reset }}");
+        assert!(sensor.validate().is_ok());
+        assert_eq!(
+            "{{ value_json.last_reset }}",
+            serde_json::to_value(&sensor).unwrap()["lrst_val_tpl"]
+        );
+    }
+
+    #[test]
+    fn rejects_last_reset_value_template_without_total_state_class() {
+        let sensor = Sensor::default()
+            .state_class(StateClass::TotalIncreasing)
+            .last_reset_value_template("{{ value_json.last_reset }}");
+        assert!(sensor.validate().is_err());
+    }
+
+    #[test]
+    fn can_serialize_null_device_class() {
+        let json = serde_json::to_value(Sensor::default().null_device_class()).unwrap();
+        assert_eq!(Some(&serde_json::Value::Null), json.get("dev_cla"));
+    }
+
+    #[test]
+    fn rejects_state_class_with_timestamp_device_class() {
+        let sensor = Sensor::default()
+            .device_class(SensorDeviceClass::Timestamp)
+            .state_class(StateClass::Measurement);
+        assert!(sensor.validate().is_err());
+    }
+
+    #[test]
+    fn accepts_state_class_with_power_device_class() {
+        let sensor = Sensor::default()
+            .device_class(SensorDeviceClass::Power)
+            .state_class(StateClass::Measurement);
+        assert!(sensor.validate().is_ok());
+    }
+}
@@ -1,7 +1,8 @@
 use super::common::Qos;
-use super::common::{Availability, Device, EntityCategory, Origin};
-use crate::Entity;
-use serde_derive::Serialize;
+use super::common::{Availability, Device, EntityCategory, OnOffPayloads, Origin};
+use crate::{Entity, validate_topics};
+use anyhow::{Result, bail};
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Siren"
@@ -97,7 +98,8 @@ use serde_derive::Serialize;
 /// mosquitto_pub -h 127.0.0.1 -t home/alarm/siren1 -m "ON"
 /// ```
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Siren {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -105,11 +107,11 @@ pub struct Siren {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -336,6 +338,13 @@ impl Siren {
         self
     }
 
+    /// Sets both `payload_on` and `payload_off`.
+    pub fn on_off_payloads(mut self, payloads: OnOffPayloads) -> Self {
+        self.payload_on = Some(payloads.on);
+        self.payload_off = Some(payloads.off);
+        self
+    }
+
     /// The payload that represents `off` state. If specified, will be used for both comparing to the value in the `state_topic` (see `value_template` and `state_off` for details) and sending as `off` command to the `command_topic`.
     pub fn payload_off<T: Into<String>>(mut self, payload_off: T) -> Self {
         self.payload_off = Some(payload_off.into());
@@ -451,3 +460,119 @@ impl From<Siren> for Entity {
         Entity::Siren(value)
     }
 }
+
+impl Siren {
+    /// Checks that a custom `command_template` can carry the turn on parameters the siren declares support for:
+    /// `tone` with `available_tones`, `volume_level` with `support_volume_set` and `duration` with `support_duration`.
+    /// `support_volume_set` and `support_duration` default to `true`, as in Home Assistant.
+    /// Without a `command_template` the parameters are part of the default JSON payload.
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        let Some(command_template) = &self.command_template else {
+            return Ok(());
+        };
+        let parameters = [
+            ("available_tones", self.available_tones.is_some(), "tone"),
+            (
+                "support_volume_set",
+                self.support_volume_set.unwrap_or(true),
+                "volume_level",
+            ),
+            (
+                "support_duration",
+                self.support_duration.unwrap_or(true),
+                "duration",
+            ),
+        ];
+        for (option, enabled, variable) in parameters {
+            if enabled && !uses_template_variable(command_template, variable) {
+                bail!("{option} is set but command_template doesn't use the '{variable}' variable");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `template` uses `variable` as a whole identifier outside of `{# #}` comments,
+/// e.g. `tone` in `{{ tone }}` but not in `{{ value_json.tones }}`.
+fn uses_template_variable(template: &str, variable: &str) -> bool {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    let mut rest = template;
+    while !rest.is_empty() {
+        let (code, after) = rest.split_once("{#").unwrap_or((rest, ""));
+        let found = code.match_indices(variable).any(|(start, _)| {
+            let before = code[..start].chars().next_back();
+            let after = code[start + variable.len()..].chars().next();
+            !before.is_some_and(|c| is_identifier(c) || c == '.')
+                && !after.is_some_and(is_identifier)
+        });
+        if found {
+            return true;
+        }
+        rest = after.split_once("#}").map_or("", |(_, rest)| rest);
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_tones_not_carried_by_command_template() {
+        let siren = Siren::default()
+            .available_tones(vec!["ping", "siren"])
+            .command_template("{{ value }}");
+        assert!(siren.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_volume_not_carried_by_command_template() {
+        let siren = Siren::default()
+            .support_volume_set(true)
+            .support_duration(false)
+            .command_template("{{ value }}");
+        assert!(siren.validate().is_err());
+    }
+
+    #[test]
+    fn volume_and_duration_are_supported_by_default() {
+        let siren = Siren::default().command_template("{{ value }}");
+        assert!(siren.validate().is_err());
+        let siren = Siren::default()
+            .support_volume_set(false)
+            .support_duration(false)
+            .command_template("{{ value }}");
+        assert!(siren.validate().is_ok());
+    }
+
+    #[test]
+    fn tone_must_be_used_as_a_whole_variable() {
+        let siren = |command_template: &str| {
+            Siren::default()
+                .available_tones(vec!["ping", "siren"])
+                .support_volume_set(false)
+                .support_duration(false)
+                .command_template(command_template)
+        };
+        assert!(siren("{{ value_json.tones }}").validate().is_err());
+        assert!(siren("{{ value }}{# tone #}").validate().is_err());
+        assert!(siren("{{ value }},{{tone}}").validate().is_ok());
+    }
+
+    #[test]
+    fn accepts_parameters_carried_by_command_template() {
+        let siren = Siren::default()
+            .available_tones(vec!["ping", "siren"])
+            .command_template("{{ value }},{{ tone }},{{ volume_level }},{{ duration }}");
+        assert!(siren.validate().is_ok());
+    }
+
+    #[test]
+    fn accepts_parameters_without_command_template() {
+        let siren = Siren::default()
+            .available_tones(vec!["ping", "siren"])
+            .support_volume_set(true);
+        assert!(siren.validate().is_ok());
+    }
+}
@@ -1,8 +1,8 @@
 use super::common::Qos;
-use super::common::{Availability, Device, EntityCategory, Origin};
+use super::common::{Availability, Device, EntityCategory, OnOffPayloads, Origin};
 use super::device_classes::SwitchDeviceClass;
 use crate::Entity;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Switch"
@@ -105,7 +105,8 @@ use serde_derive::Serialize;
 ///       payload_off: "0"
 /// ```
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Switch {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -113,11 +114,11 @@ pub struct Switch {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -324,6 +325,13 @@ impl Switch {
         self
     }
 
+    /// Sets both `payload_on` and `payload_off`.
+    pub fn on_off_payloads(mut self, payloads: OnOffPayloads) -> Self {
+        self.payload_on = Some(payloads.on);
+        self.payload_off = Some(payloads.off);
+        self
+    }
+
     /// The payload that represents `off` state. If specified, will be used for both comparing to the value in the `state_topic` (see `value_template` and `state_off` for details) and sending as `off` command to the `command_topic`.
     pub fn payload_off<T: Into<String>>(mut self, payload_off: T) -> Self {
         self.payload_off = Some(payload_off.into());
@@ -424,3 +432,44 @@ impl From<Switch> for Entity {
         Entity::Switch(value)
     }
 }
+
+impl Switch {
+    /// An outlet with the `outlet` device class, switched by `ON` and `OFF` payloads on `command_topic`
+    /// and reporting the same payloads on `state_topic`.
+    pub fn outlet<C: Into<String>, S: Into<String>>(command_topic: C, state_topic: S) -> Self {
+        Self::default()
+            .device_class(SwitchDeviceClass::Outlet)
+            .command_topic(command_topic)
+            .state_topic(state_topic)
+            .on_off_payloads(OnOffPayloads::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_set_on_off_payloads() {
+        let switch = Switch::default().on_off_payloads(OnOffPayloads::default());
+        let json = serde_json::to_value(&switch).unwrap();
+        assert_eq!("ON", json["pl_on"]);
+        assert_eq!("OFF", json["pl_off"]);
+
+        let switch =
+            Switch::default().on_off_payloads(OnOffPayloads::custom("1", String::from("0")));
+        assert_eq!(Some("1".to_string()), switch.payload_on);
+        assert_eq!(Some("0".to_string()), switch.payload_off);
+    }
+
+    #[test]
+    fn outlet_preset() {
+        let switch = Switch::outlet("kitchen/outlet/set", "kitchen/outlet/state");
+        let json = serde_json::to_value(&switch).unwrap();
+        assert_eq!("outlet", json["dev_cla"]);
+        assert_eq!("kitchen/outlet/set", json["cmd_t"]);
+        assert_eq!("kitchen/outlet/state", json["stat_t"]);
+        assert_eq!("ON", json["pl_on"]);
+        assert_eq!("OFF", json["pl_off"]);
+    }
+}
@@ -1,7 +1,7 @@
 use super::common::Qos;
 use super::common::{Availability, Device, EntityCategory, Origin};
 use crate::Entity;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Text"
@@ -49,7 +49,8 @@ use serde_derive::Serialize;
 /// ```
 ///
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Text {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -57,11 +58,11 @@ pub struct Text {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -110,7 +111,7 @@ pub struct Text {
 
     /// The mode off the text entity. Must be either `text` or `password`.
     #[serde(rename = "mode", skip_serializing_if = "Option::is_none")]
-    pub mode: Option<String>,
+    pub mode: Option<TextMode>,
 
     /// The name of the text entity. Can be set to `null` if only the device name is relevant.
     #[serde(rename = "name", skip_serializing_if = "Option::is_none")]
@@ -239,8 +240,8 @@ impl Text {
     }
 
     /// The mode off the text entity. Must be either `text` or `password`.
-    pub fn mode<T: Into<String>>(mut self, mode: T) -> Self {
-        self.mode = Some(mode.into());
+    pub fn mode(mut self, mode: TextMode) -> Self {
+        self.mode = Some(mode);
         self
     }
 
@@ -335,3 +336,30 @@ impl From<Text> for Entity {
         Entity::Text(value)
     }
 }
+
+/// How the text entity is displayed in the UI.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextMode {
+    /// The text is shown as typed.
+    #[default]
+    #[serde(rename = "text")]
+    Text,
+
+    /// The text is masked, like a password.
+    #[serde(rename = "password")]
+    Password,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_password_mode() {
+        let text = Text::default().mode(TextMode::Password);
+        assert_eq!(
+            serde_json::json!("password"),
+            serde_json::to_value(&text).unwrap()["mode"]
+        );
+    }
+}
@@ -1,9 +1,10 @@
+use super::device_classes::NumberDeviceClass;
 use derive_more::From;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// Units of measurement
 #[allow(dead_code)]
-#[derive(From, Clone, Debug, PartialEq, Serialize)]
+#[derive(From, Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Unit {
     #[from(PowerUnit)]
@@ -60,9 +61,86 @@ pub enum Unit {
     DataRateUnit(DataRateUnit),
 }
 
+impl Unit {
+    /// Returns the number device classes accepting this unit, following the units documented for each
+    /// [NumberDeviceClass]. Empty if no device class accepts the unit.
+    pub fn compatible_device_classes(&self) -> &'static [NumberDeviceClass] {
+        use NumberDeviceClass::*;
+        match self {
+            Unit::Power(_) => &[Power],
+            Unit::Volt(_) => &[Voltage],
+            Unit::Energy(_) => &[Energy, EnergyStorage],
+            Unit::Electrical(ElectricalUnit::CurrentAmpere) => &[Current],
+            Unit::Electrical(ElectricalUnit::VoltAmpere) => &[ApparentPower],
+            Unit::Angle(_) => &[WindDirection],
+            Unit::Currency(_) => &[Monetary],
+            Unit::Temperature(_) => &[Temperature],
+            Unit::Time(
+                TimeUnit::Milliseconds
+                | TimeUnit::Seconds
+                | TimeUnit::Minutes
+                | TimeUnit::Hours
+                | TimeUnit::Days,
+            ) => &[Duration],
+            Unit::Time(_) => &[],
+            Unit::Length(
+                LengthUnit::Millimeters | LengthUnit::Centimeters | LengthUnit::Inches,
+            ) => &[Distance, Precipitation],
+            Unit::Length(LengthUnit::Feet) => &[],
+            Unit::Length(_) => &[Distance],
+            Unit::Frequency(_) => &[Frequency],
+            Unit::Pressure(_) => &[AtmosphericPressure, Pressure],
+            Unit::Volume(VolumeUnit::CubicMeters | VolumeUnit::CubicFeet) => {
+                &[Gas, Volume, VolumeStorage, Water]
+            }
+            Unit::Volume(VolumeUnit::Liters | VolumeUnit::Gallons) => {
+                &[Volume, VolumeStorage, Water]
+            }
+            Unit::Volume(VolumeUnit::Milliliters | VolumeUnit::FluidOunce) => {
+                &[Volume, VolumeStorage]
+            }
+            Unit::VolumeFlowRate(_) => &[VolumeFlowRate],
+            Unit::Area(_) => &[Area],
+            Unit::Mass(_) => &[Weight],
+            Unit::Conductivity(_) => &[],
+            Unit::Light(_) => &[Illuminance],
+            Unit::Uv(_) => &[],
+            Unit::Percentage(_) => &[Battery, Humidity, Moisture, PowerFactor],
+            Unit::Irradiation(_) => &[Irradiance],
+            Unit::Precipitation(_) => &[PrecipitationIntensity],
+            Unit::Concentration(ConcentrationUnit::MicrogramsPerCubicMeter) => &[
+                NitrogenDioxide,
+                NitrogenMonoxide,
+                NitrousOxide,
+                Ozone,
+                Pm1,
+                Pm25,
+                Pm10,
+                SulphurDioxide,
+                VolatileOrganicCompounds,
+            ],
+            Unit::Concentration(ConcentrationUnit::PartsPerMillion) => {
+                &[CarbonDioxide, CarbonMonoxide, VolatileOrganicCompoundsParts]
+            }
+            Unit::Concentration(ConcentrationUnit::PartsPerBillion) => {
+                &[VolatileOrganicCompoundsParts]
+            }
+            Unit::Concentration(_) => &[],
+            Unit::Speed(
+                SpeedUnit::MillimetersPerDay | SpeedUnit::InchesPerDay | SpeedUnit::InchesPerHour,
+            ) => &[PrecipitationIntensity, Speed],
+            Unit::Speed(_) => &[Speed, WindSpeed],
+            Unit::SignalStrength(SignalStrengthUnit::Decibels) => &[SignalStrength, SoundPressure],
+            Unit::SignalStrength(SignalStrengthUnit::DecibelsMilliwatt) => &[SignalStrength],
+            Unit::Data(_) => &[DataSize],
+            Unit::DataRateUnit(_) => &[DataRate],
+        }
+    }
+}
+
 /// Power units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum PowerUnit {
     #[serde(rename = "W")]
     Watt,
@@ -72,7 +150,7 @@ pub enum PowerUnit {
 
 /// Volt unit
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum VoltUnit {
     #[serde(rename = "V")]
     Volt,
@@ -80,7 +158,7 @@ pub enum VoltUnit {
 
 /// Energy units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EnergyUnit {
     #[serde(rename = "Wh")]
     WattHour,
@@ -90,7 +168,7 @@ pub enum EnergyUnit {
 
 /// Electrical units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ElectricalUnit {
     #[serde(rename = "A")]
     CurrentAmpere,
@@ -100,7 +178,7 @@ pub enum ElectricalUnit {
 
 /// Angle units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum AngleUnit {
     #[serde(rename = "°")]
     Degree,
@@ -108,7 +186,7 @@ pub enum AngleUnit {
 
 /// Currency units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum CurrencyUnit {
     #[serde(rename = "€")]
     Euro,
@@ -120,7 +198,7 @@ pub enum CurrencyUnit {
 
 /// Temperature units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TempUnit {
     #[serde(rename = "°C")]
     Celsius,
@@ -132,7 +210,7 @@ pub enum TempUnit {
 
 /// Time units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TimeUnit {
     #[serde(rename = "μs")]
     Microseconds,
@@ -156,7 +234,7 @@ pub enum TimeUnit {
 
 /// Length units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum LengthUnit {
     #[serde(rename = "mm")]
     Millimeters,
@@ -179,7 +257,7 @@ pub enum LengthUnit {
 
 /// Frequency units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum FrequencyUnit {
     #[serde(rename = "Hz")]
     Hertz,
@@ -189,7 +267,7 @@ pub enum FrequencyUnit {
 
 /// Pressure units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum PressureUnit {
     #[serde(rename = "Pa")]
     Pa,
@@ -207,7 +285,7 @@ pub enum PressureUnit {
 
 /// Volume units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum VolumeUnit {
     #[serde(rename = "L")]
     Liters,
@@ -226,7 +304,7 @@ pub enum VolumeUnit {
 
 /// Volume Flow Rate units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum VolumeFlowRateUnit {
     #[serde(rename = "m³/h")]
     CubicMetersPerHour,
@@ -235,7 +313,7 @@ pub enum VolumeFlowRateUnit {
 }
 /// Area units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum AreaUnit {
     #[serde(rename = "m²")]
     SquareMeters,
@@ -243,7 +321,7 @@ pub enum AreaUnit {
 
 /// Mass units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum MassUnit {
     #[serde(rename = "g")]
     Grams,
@@ -262,7 +340,7 @@ pub enum MassUnit {
 
 /// Conductivity units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ConductivityUnit {
     #[serde(rename = "µS/cm")]
     Conductivity,
@@ -270,7 +348,7 @@ pub enum ConductivityUnit {
 
 /// Light units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum LightUnit {
     #[serde(rename = "lx")]
     Lux,
@@ -278,7 +356,7 @@ pub enum LightUnit {
 
 /// UV Index units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum UvUnit {
     #[serde(rename = "UV index")]
     UvIndex,
@@ -286,7 +364,7 @@ pub enum UvUnit {
 
 /// Percentage units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum PercentageUnit {
     #[serde(rename = "%")]
     Percentage,
@@ -294,7 +372,7 @@ pub enum PercentageUnit {
 
 /// Irradiation units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum IrradiationUnit {
     #[serde(rename = "W/m²")]
     WattsPerSquareMeter,
@@ -302,7 +380,7 @@ pub enum IrradiationUnit {
 
 /// Precipitation units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum PrecipitationUnit {
     #[serde(rename = "mm/h")]
     MillimetersPerHour,
@@ -310,7 +388,7 @@ pub enum PrecipitationUnit {
 
 /// Concentration units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ConcentrationUnit {
     #[serde(rename = "µg/m³")]
     MicrogramsPerCubicMeter,
@@ -326,7 +404,7 @@ pub enum ConcentrationUnit {
 
 /// Speed units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SpeedUnit {
     #[serde(rename = "mm/d")]
     MillimetersPerDay,
@@ -344,7 +422,7 @@ pub enum SpeedUnit {
 
 /// Signal_strength units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SignalStrengthUnit {
     #[serde(rename = "dB")]
     Decibels,
@@ -354,7 +432,7 @@ pub enum SignalStrengthUnit {
 
 /// Data units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DataUnit {
     #[serde(rename = "bit")]
     Bits,
@@ -402,7 +480,7 @@ pub enum DataUnit {
 
 /// Data rate units
 #[allow(dead_code)]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DataRateUnit {
     #[serde(rename = "bit/s")]
     BitsPerSecond,
@@ -427,3 +505,25 @@ pub enum DataRateUnit {
     #[serde(rename = "GiB/s")]
     GibibytesPerSecond,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentage_is_compatible_with_battery_and_humidity() {
+        let classes = Unit::Percentage(PercentageUnit::Percentage).compatible_device_classes();
+        assert!(classes.contains(&NumberDeviceClass::Battery));
+        assert!(classes.contains(&NumberDeviceClass::Humidity));
+        assert!(!classes.contains(&NumberDeviceClass::Temperature));
+    }
+
+    #[test]
+    fn unit_without_device_class_has_no_compatible_classes() {
+        assert!(
+            Unit::Uv(UvUnit::UvIndex)
+                .compatible_device_classes()
+                .is_empty()
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use super::common::Qos;
 use super::common::{Availability, Device, EntityCategory, Origin};
-use crate::Entity;
-use serde_derive::Serialize;
+use crate::{Entity, validate_topics};
+use anyhow::{Result, bail};
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT Valve"
@@ -149,7 +150,8 @@ use serde_derive::Serialize;
 /// mosquitto_pub -h 127.0.0.1 -t home-assistant/valve/set -m "CLOSE"
 /// ```
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Valve {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -157,11 +159,11 @@ pub struct Valve {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -534,3 +536,131 @@ impl From<Valve> for Entity {
         Entity::Valve(value)
     }
 }
+
+/// A state reported by a valve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValveState {
+    Open,
+    Opening,
+    Closed,
+    Closing,
+    /// The position of a valve reporting its position, scaled to the 0 (closed) to 100 (open) range.
+    Position(i32),
+}
+
+impl Valve {
+    /// Interprets a payload received on `state_topic` using the configured state payloads.
+    ///
+    /// When `reports_position` is set, numeric payloads are interpreted as a position scaled from the
+    /// (`position_closed`...`position_open`) range, and only the `opening` and `closing` state payloads are accepted.
+    pub fn interpret_state(&self, payload: &str) -> Option<ValveState> {
+        let reports_position = self.reports_position.unwrap_or(false);
+        if payload == self.state_opening.as_deref().unwrap_or("opening") {
+            return Some(ValveState::Opening);
+        }
+        if payload == self.state_closing.as_deref().unwrap_or("closing") {
+            return Some(ValveState::Closing);
+        }
+        if reports_position {
+            let position: i32 = payload.trim().parse().ok()?;
+            let closed = self.position_closed.unwrap_or(0);
+            let open = self.position_open.unwrap_or(100);
+            if open == closed {
+                return None;
+            }
+            let scaled = (position - closed) * 100 / (open - closed);
+            return Some(ValveState::Position(scaled.clamp(0, 100)));
+        }
+        if payload == self.state_open.as_deref().unwrap_or("open") {
+            return Some(ValveState::Open);
+        }
+        if payload == self.state_closed.as_deref().unwrap_or("closed") {
+            return Some(ValveState::Closed);
+        }
+        None
+    }
+}
+
+impl Valve {
+    /// Checks that the payloads match the `reports_position` mode: when the valve reports its position,
+    /// `payload_open`, `payload_close`, `state_open` and `state_closed` aren't allowed, as the position drives
+    /// the state. Otherwise, `position_open` and `position_closed` aren't allowed.
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)?;
+        let (mode, forbidden): (_, Vec<(&str, bool)>) = if self.reports_position == Some(true) {
+            (
+                "reports_position is true",
+                vec![
+                    ("payload_open", self.payload_open.is_some()),
+                    ("payload_close", self.payload_close.is_some()),
+                    ("state_open", self.state_open.is_some()),
+                    ("state_closed", self.state_closed.is_some()),
+                ],
+            )
+        } else {
+            (
+                "reports_position is false",
+                vec![
+                    ("position_open", self.position_open.is_some()),
+                    ("position_closed", self.position_closed.is_some()),
+                ],
+            )
+        };
+        if let Some((name, _)) = forbidden.iter().find(|(_, set)| *set) {
+            bail!("{name} is not allowed when {mode}");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interprets_configured_state_payloads() {
+        let valve = Valve::default().state_open("OPEN").state_closed("CLOSED");
+        assert_eq!(Some(ValveState::Open), valve.interpret_state("OPEN"));
+        assert_eq!(Some(ValveState::Closed), valve.interpret_state("CLOSED"));
+        assert_eq!(Some(ValveState::Opening), valve.interpret_state("opening"));
+        assert_eq!(None, valve.interpret_state("open"));
+        assert_eq!(None, valve.interpret_state("50"));
+    }
+
+    #[test]
+    fn interprets_position_of_position_reporting_valve() {
+        let valve = Valve::default()
+            .reports_position(true)
+            .position_closed(40)
+            .position_open(140);
+        assert_eq!(Some(ValveState::Position(0)), valve.interpret_state("40"));
+        assert_eq!(Some(ValveState::Position(60)), valve.interpret_state("100"));
+        assert_eq!(Some(ValveState::Closing), valve.interpret_state("closing"));
+        assert_eq!(None, valve.interpret_state("open"));
+    }
+
+    #[test]
+    fn position_mode_rejects_open_close_payloads() {
+        let valve = Valve::default()
+            .reports_position(true)
+            .position_open(255)
+            .position_closed(0);
+        assert!(valve.validate().is_ok());
+        assert!(valve.clone().payload_open("OPEN").validate().is_err());
+        assert!(valve.state_closed("closed").validate().is_err());
+    }
+
+    #[test]
+    fn payload_mode_rejects_position_fields() {
+        let valve = Valve::default().payload_open("OPEN").payload_close("CLOSE");
+        assert!(valve.validate().is_ok());
+        assert!(valve.clone().position_open(100).validate().is_err());
+        assert!(
+            valve
+                .reports_position(false)
+                .position_closed(0)
+                .validate()
+                .is_err()
+        );
+    }
+}
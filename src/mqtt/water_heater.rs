@@ -1,9 +1,10 @@
 use super::common::Qos;
 use super::common::TemperatureUnit;
 use super::common::{Availability, Device, EntityCategory, Origin};
-use crate::Entity;
+use crate::{Entity, validate_topics};
+use anyhow::Result;
 pub use rust_decimal::Decimal;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// ---
 /// title: "MQTT water heater"
@@ -83,7 +84,8 @@ use serde_derive::Serialize;
 /// ```
 ///
 ///
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WaterHeater {
     /// Replaces `~` with this value in any MQTT topic attribute.
     /// [See Home Assistant documentation](https://www.home-assistant.io/integrations/mqtt/#using-abbreviations-and-base-topic)
@@ -91,11 +93,11 @@ pub struct WaterHeater {
     pub topic_prefix: Option<String>,
 
     /// It is encouraged to add additional information about the origin that supplies MQTT entities via MQTT discovery by adding the origin option (can be abbreviated to o) to the discovery payload. Note that these options also support abbreviations. Information of the origin will be logged to the core event log when an item is discovered or updated.
-    #[serde(rename = "o")]
+    #[serde(rename = "o", skip_serializing_if = "Origin::is_empty")]
     pub origin: Origin,
 
     /// Information about the device this button is a part of to tie it into the [device registry](https://developers.home-assistant.io/docs/en/device_registry_index.html). Only works when [`unique_id`](#unique_id) is set. At least one of identifiers or connections must be present to identify the device.
-    #[serde(rename = "dev")]
+    #[serde(rename = "dev", skip_serializing_if = "Device::is_empty")]
     pub device: Device,
 
     /// A list of MQTT topics subscribed to receive availability (online/offline) updates. Must not be used together with `availability_topic`.
@@ -555,3 +557,48 @@ impl From<WaterHeater> for Entity {
         Entity::WaterHeater(value)
     }
 }
+
+impl WaterHeater {
+    /// Checks the topics of the water heater.
+    /// Conflicting but accepted configuration is reported by [WaterHeater::warnings].
+    pub fn validate(&self) -> Result<()> {
+        validate_topics(self)
+    }
+
+    /// Reports configuration that is valid but likely a mistake, e.g. an `off` mode together with
+    /// `power_command_topic`, so turning the water heater off can be done in two ways that may disagree.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.power_command_topic.is_some()
+            && self.modes.iter().flatten().any(|mode| mode == "off")
+        {
+            warnings.push(
+                "the off mode is configured while power_command_topic is set, so the power state and the mode can conflict"
+                    .to_string(),
+            );
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warns_about_off_mode_with_power_topic() {
+        let water_heater = WaterHeater::default()
+            .power_command_topic("boiler/power/set")
+            .modes(vec!["off", "eco"]);
+        assert!(water_heater.validate().is_ok());
+        assert_eq!(1, water_heater.warnings().len());
+    }
+
+    #[test]
+    fn no_warning_without_off_mode() {
+        let water_heater = WaterHeater::default()
+            .power_command_topic("boiler/power/set")
+            .modes(vec!["eco"]);
+        assert!(water_heater.warnings().is_empty());
+    }
+}
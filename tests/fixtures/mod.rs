@@ -0,0 +1,32 @@
+//! Loading and regenerating the golden JSON files in `tests/resources/golden`.
+//!
+//! Run the tests with `UPDATE_GOLDEN=1` to rewrite the files from the current serialization.
+
+use assert_json_diff::assert_json_eq;
+use serde::Serialize;
+use serde_json::Value;
+use std::{env, fs, path::PathBuf};
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/resources/golden")
+        .join(format!("{name}.json"))
+}
+
+/// Compares the serialization of `value` with the golden file `name`,
+/// or rewrites the file when `UPDATE_GOLDEN` is set.
+pub fn assert_golden<T: Serialize>(name: &str, value: &T) {
+    let actual = serde_json::to_value(value).unwrap();
+    let path = golden_path(name);
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        let mut json = serde_json::to_string_pretty(&actual).unwrap();
+        json.push('\n');
+        fs::write(&path, json).unwrap();
+        return;
+    }
+    let expected: Value = serde_json::from_str(
+        &fs::read_to_string(&path).unwrap_or_else(|e| panic!("can't read {}: {e}", path.display())),
+    )
+    .unwrap();
+    assert_json_eq!(expected, actual);
+}
@@ -0,0 +1,69 @@
+mod fixtures;
+
+use fixtures::assert_golden;
+use ha_mqtt_discovery::mqtt::{
+    common::{Availability, AvailabilityCheck, Device, EntityCategory, Origin, Qos, StateClass},
+    cover::Cover,
+    device_classes::NumberDeviceClass,
+    number::Number,
+    units::TempUnit,
+};
+use rust_decimal_macros::dec;
+
+fn origin() -> Origin {
+    Origin::new("Golden test").with_sw_version("0.0.1")
+}
+
+fn device() -> Device {
+    Device::default()
+        .name("Living room")
+        .add_identifier("living-room-01")
+        .manufacturer("Awesome corp")
+}
+
+#[test]
+fn cover() {
+    let cover = Cover::default()
+        .topic_prefix("living_room/blind")
+        .origin(origin())
+        .device(device())
+        .availability(Availability::single(
+            AvailabilityCheck::topic("~/availability").payload_available("up"),
+        ))
+        .command_topic("~/set")
+        .name("Blind")
+        .position_closed(0)
+        .position_open(100)
+        .position_topic("~/position")
+        .set_position_topic("~/position/set")
+        .state_topic("~/state")
+        .tilt_command_topic("~/tilt/set")
+        .tilt_status_topic("~/tilt")
+        .tilt_min(0)
+        .tilt_max(6)
+        .qos(Qos::AtLeastOnce)
+        .unique_id("living_room_blind");
+    assert_golden("cover", &cover);
+}
+
+#[test]
+fn number() {
+    let number = Number::default()
+        .topic_prefix("living_room/thermostat")
+        .origin(origin())
+        .device(device())
+        .entity_category(EntityCategory::Config)
+        .command_topic("~/target/set")
+        .device_class(NumberDeviceClass::Temperature)
+        .max(dec!(30))
+        .min(dec!(5))
+        .mode("box")
+        .name("Target temperature")
+        .payload_reset("NaN")
+        .state_class(StateClass::Measurement)
+        .state_topic("~/target")
+        .step(dec!(0.5))
+        .unique_id("living_room_target_temperature")
+        .unit_of_measurement(TempUnit::Celsius);
+    assert_golden("number", &number);
+}
@@ -159,7 +159,6 @@ async fn can_publish_a_binary_sensor_configuration() {
                 },
                 "obj_id": "barometer-09AF",
                 "uniq_id": "barometer-09AF_state",
-                "avty_mode": "all",
                 "avty": [
                   {
                     "t": "~/availability"
@@ -252,7 +251,6 @@ async fn can_publish_a_number_configuration() {
                 },
                 "obj_id": "barometer-09AF",
                 "uniq_id": "barometer-09AF_temperature_drift",
-                "avty_mode": "all",
                 "avty": [
                   {
                     "t": "~/availability"
@@ -345,7 +343,6 @@ async fn can_publish_a_sensor_configuration() {
                 },
                 "obj_id": "barometer-09AF",
                 "uniq_id": "barometer-09AF_temperature",
-                "avty_mode": "all",
                 "avty": [
                   {
                     "t": "~/availability"